@@ -3,19 +3,49 @@ use std::sync::Arc;
 use smn_web_core::{plugins::plugin_static::PluginStatic, systems::{sys_core::run_server, sys_plugin::PluginManager}};
 
 pub mod plugins;
+pub mod systems;
 
 #[tokio::main]
 async fn main() {
 
     let mut manager = PluginManager::new();
-    let mut components = plugins::plugin_components::PluginComponents::new();
-
-    components.register_simple("./components/underConstruction.html");
-
-
-    manager.apply_plugin(Box::new(plugins::plugin_showcase::PluginShowcase::new()));
+    // Development mode (SMN_DEV=1) and the admin key (SMN_ADMIN_KEY) unlock developer tooling.
+    let dev_mode = std::env::var("SMN_DEV").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+    let admin_key = std::env::var("SMN_ADMIN_KEY").ok();
+
+    // File types: built-in table plus overrides from ./mime_types.json (or SMN_MIME_TYPES).
+    let mime_config = std::env::var("SMN_MIME_TYPES").unwrap_or_else(|_| "./mime_types.json".to_string());
+    match systems::sys_mime::MimeRegistry::from_config(&mime_config) {
+        Ok(registry) => systems::sys_mime::init(registry),
+        Err(e) => eprintln!("WARN: Failed to load MIME types from '{mime_config}': {e}"),
+    }
+
+    // Cross-origin embedding of components: ./cors.json (or SMN_CORS); same-origin only without it.
+    let cors_config = std::env::var("SMN_CORS").unwrap_or_else(|_| "./cors.json".to_string());
+    let cors = plugins::plugin_components::CorsConfig::from_config(&cors_config).unwrap_or_else(|e| {
+        eprintln!("WARN: Failed to load CORS config from '{cors_config}', serving same-origin only: {e}");
+        plugins::plugin_components::CorsConfig::same_origin()
+    });
+
+    // Components are declared in ./components/components.json and registered at init.
+    let showcase = plugins::plugin_showcase::PluginShowcase::new();
+    let mut components = plugins::plugin_components::PluginComponents::new()
+        .with_dev_mode(dev_mode)
+        .with_admin_key(admin_key)
+        .with_cors(cors)
+        // Template edits are pushed to open pages over /events.
+        .with_broadcast(manager.broadcast().clone());
+    // Project cards, grids and trees read the showcase's data.
+    components.register_showcase(showcase.data());
+    // Static pages get <smn-component> markers expanded server-side.
+    let includes = components.includes();
+
+    manager.apply_plugin(Box::new(showcase));
     manager.apply_plugin(Box::new(components));
-    manager.apply_plugin(Box::new(PluginStatic::new(true, vec!["html".to_string(), "pdf".to_string(), "svg".to_string()])));
+    manager.apply_plugin(Box::new(plugins::plugin_static_includes::PluginStaticIncludes::new(
+        PluginStatic::new(true, vec!["html".to_string(), "pdf".to_string(), "svg".to_string()]),
+        includes,
+    )));
     manager.init_plugins().await;
     let manager = Arc::new(manager);
 
@@ -1,2 +1,3 @@
 pub mod plugin_components;
-pub mod plugin_showcase;
\ No newline at end of file
+pub mod plugin_showcase;
+pub mod plugin_static_includes;
\ No newline at end of file
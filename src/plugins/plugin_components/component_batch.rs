@@ -0,0 +1,131 @@
+use hyper::{
+    Body, Method, Request, Response, StatusCode,
+    body::to_bytes,
+    header::{CONTENT_TYPE, HeaderValue},
+};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentError, ComponentProps, HOT_RELOAD_HEADER,
+    component_error::Problem,
+    component_include::ComponentIncludes, respond_status,
+    run_component,
+};
+
+// Upper bound on components per batch request.
+const MAX_BATCH_SIZE: usize = 64;
+
+/// One entry of a `/components/_batch` request body:
+/// `[ { "name": "header", "compArgs": ["Hi"] }, { "name": "card", "compProps": { ... } } ]`
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct BatchItem {
+    name: String,
+    #[serde(default)]
+    compArgs: Vec<String>,
+    #[serde(default)]
+    compProps: serde_json::Map<String, serde_json::Value>,
+}
+
+/// One entry of the response, in request order.
+/// Failed entries carry `error`; their `html` is empty (or the error fragment in development mode).
+#[derive(Serialize)]
+struct BatchResult {
+    name: String,
+    status: u16,
+    html: String,
+    /// Assets to add to the page `<head>` (deduplicated by the client)
+    stylesheets: Vec<String>,
+    scripts: Vec<String>,
+    /// Problem details for failed entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Problem>,
+}
+
+/// POST /components/_batch: run every requested component concurrently.
+/// A failing component only fails its own entry; the batch itself answers 200.
+pub(crate) async fn handle_batch(
+    includes: ComponentIncludes,
+    ctx: &ComponentContext,
+    method: &Method,
+    req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    if *method != Method::POST {
+        return Ok(respond_status(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "405 Method Not Allowed: POST a JSON array",
+        ));
+    }
+
+    let full = to_bytes(req.into_body()).await.unwrap_or_default();
+    let items: Vec<BatchItem> = match serde_json::from_slice(&full) {
+        Ok(items) => items,
+        Err(e) => {
+            let err = ComponentError::BadArgs(vec![format!(
+                "malformed JSON body, expected [{{ \"name\": ..., \"compArgs\": [...] }}]: {e}"
+            )]);
+            // Batch callers parse JSON, so never answer with the dev-mode HTML fragment
+            return Ok(err.into_response("_batch", false));
+        }
+    };
+    if items.len() > MAX_BATCH_SIZE {
+        return Ok(respond_status(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            &format!("413 Payload Too Large: at most {MAX_BATCH_SIZE} components per batch"),
+        ));
+    }
+
+    // Spawn all, then collect in request order.
+    let tasks: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let includes = includes.clone();
+            let ctx = ctx.clone();
+            let name = item.name.clone();
+            let task = tokio::spawn(async move {
+                let props = ComponentProps::new(item.compProps);
+                run_component(&includes, &ctx, &item.name, item.compArgs, props).await
+            });
+            (name, task)
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (name, task) in tasks {
+        let result = task.await.unwrap_or_else(|e| {
+            eprintln!("Component '{name}' panicked in batch: {e}");
+            Err(ComponentError::Render("handler panicked".to_string()))
+        });
+        results.push(match result {
+            Ok(output) => BatchResult {
+                name,
+                status: StatusCode::OK.as_u16(),
+                html: output.html,
+                stylesheets: output.stylesheets,
+                scripts: output.scripts,
+                error: None,
+            },
+            Err(e) => BatchResult {
+                status: e.status().as_u16(),
+                // Development mode: a visible error box to mount in place of the component
+                html: if ctx.dev_mode { e.html_fragment(&name) } else { String::new() },
+                stylesheets: Vec::new(),
+                scripts: Vec::new(),
+                error: Some(e.problem(&name, ctx.dev_mode)),
+                name,
+            },
+        });
+    }
+
+    let json = serde_json::to_string(&results).unwrap_or_else(|_| "[]".to_string());
+    let mut resp = Response::new(Body::from(json));
+    resp.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    if ctx.dev_mode {
+        resp.headers_mut().insert(HOT_RELOAD_HEADER, HeaderValue::from_static("on"));
+    }
+    Ok(resp)
+}
@@ -0,0 +1,216 @@
+use hyper::{
+    Body, HeaderMap, Method, Request,
+    header::{ACCEPT_LANGUAGE, COOKIE, REFERER},
+};
+use smn_web_core::structs::struct_plugin::PluginContext;
+use std::collections::HashMap;
+
+/// Locale used when the request doesn't ask for one.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// The request a component is rendered for, built in `PluginComponents::plugin_handle`
+/// (or by `PluginStaticIncludes` for server-side includes). Nested components share their parent's.
+#[derive(Clone)]
+pub struct ComponentContext {
+    pub method: Method,
+    /// Path of the request: `/components/<name>`, `/components/_batch`, or the page being served
+    pub path: String,
+    /// Raw query string (without `?`)
+    pub query: String,
+    pub headers: HeaderMap,
+    pub cookies: HashMap<String, String>,
+    /// `Referer` header, if any
+    pub referrer: Option<String>,
+    /// From `?lang=`, then the `lang` cookie, then `Accept-Language`; `DEFAULT_LOCALE` otherwise
+    pub locale: String,
+    /// Server runs in development mode (verbose errors, developer tooling)
+    pub dev_mode: bool,
+    /// `smn_web_core` context, e.g. for `broadcast.send_update_id`
+    pub plugin: PluginContext,
+}
+
+impl ComponentContext {
+    pub fn from_request(req: &Request<Body>, plugin: &PluginContext, dev_mode: bool) -> Self {
+        let headers = req.headers().clone();
+        let query = req.uri().query().unwrap_or_default().to_string();
+        let cookies = parse_cookies(&headers);
+        let referrer = headers
+            .get(REFERER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        // An invalid value in one source falls through to the next
+        let locale = query_param(&query, "lang")
+            .filter(|l| is_language_tag(l))
+            .or_else(|| cookies.get("lang").filter(|l| is_language_tag(l)).cloned())
+            .or_else(|| preferred_language(&headers))
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+
+        Self {
+            method: req.method().clone(),
+            path: req.uri().path().to_string(),
+            query,
+            headers,
+            cookies,
+            referrer,
+            locale,
+            dev_mode,
+            plugin: plugin.clone(),
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies.get(name).map(String::as_str)
+    }
+
+    /// First value of a query parameter, URL-decoded.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        query_param(&self.query, name)
+    }
+
+    /// Primary language subtag of the locale: "de-AT" → "de".
+    pub fn language(&self) -> &str {
+        self.locale.split(['-', '_']).next().unwrap_or(&self.locale)
+    }
+
+    /// Path of the page the component is mounted in: the page itself for server-side includes,
+    /// otherwise the referrer's path (client-side mounts).
+    pub fn page_path(&self) -> Option<String> {
+        if !self.path.starts_with("/components") {
+            return Some(self.path.clone());
+        }
+        let referrer = self.referrer.as_deref()?;
+        let after_scheme = referrer.split_once("://").map_or(referrer, |(_, rest)| rest);
+        let path = after_scheme.find('/').map_or("/", |i| &after_scheme[i..]);
+        Some(path.split(['?', '#']).next().unwrap_or(path).to_string())
+    }
+}
+
+fn parse_cookies(headers: &HeaderMap) -> HashMap<String, String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| {
+            let (k, v) = pair.split_once('=')?;
+            Some((k.trim().to_string(), v.trim().trim_matches('"').to_string()))
+        })
+        .collect()
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        (k == name).then(|| {
+            urlencoding::decode(&v.replace('+', " "))
+                .map(|s| s.into_owned())
+                .unwrap_or_default()
+        })
+    })
+}
+
+/// Highest-weighted valid entry of `Accept-Language` (ignores `*` and `q=0`).
+fn preferred_language(headers: &HeaderMap) -> Option<String> {
+    let header = headers.get(ACCEPT_LANGUAGE)?.to_str().ok()?;
+    header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.trim().split(';');
+            let tag = parts.next()?.trim();
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (is_language_tag(tag) && q > 0.0).then(|| (tag.to_string(), q))
+        })
+        .fold(None, |best: Option<(String, f32)>, (tag, q)| match best {
+            Some((_, best_q)) if best_q >= q => best,
+            _ => Some((tag, q)),
+        })
+        .map(|(tag, _)| tag)
+}
+
+/// Letters, digits and dashes only (e.g. "en", "pt-BR"), so locales are safe to echo into HTML.
+fn is_language_tag(s: &str) -> bool {
+    !s.is_empty() && s.len() <= 35 && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smn_web_core::systems::sys_plugin::PluginManager;
+
+    fn request(uri: &str, headers: &[(&str, &str)]) -> ComponentContext {
+        let mut builder = Request::builder().uri(uri);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let plugin = PluginContext { broadcast: PluginManager::new().broadcast().clone() };
+        ComponentContext::from_request(&builder.body(Body::empty()).unwrap(), &plugin, false)
+    }
+
+    fn locale(uri: &str, headers: &[(&str, &str)]) -> String {
+        request(uri, headers).locale
+    }
+
+    #[test]
+    fn locale_precedence_query_cookie_header_default() {
+        let all = [("cookie", "lang=fr"), ("accept-language", "de-AT, de;q=0.8")];
+        assert_eq!(locale("/x?lang=pt-BR", &all), "pt-BR");
+        assert_eq!(locale("/x", &all), "fr");
+        assert_eq!(locale("/x", &all[1..]), "de-AT");
+        assert_eq!(locale("/x", &[]), DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn invalid_locale_source_falls_through_to_next() {
+        let all = [("cookie", "lang=fr"), ("accept-language", "de")];
+        assert_eq!(locale("/x?lang=%3Cscript%3E", &all), "fr");
+        assert_eq!(locale("/x?lang=", &all), "fr");
+        assert_eq!(locale("/x?lang=%3Cb%3E", &[("cookie", "lang=\"x y\""), all[1]]), "de");
+        assert_eq!(locale("/x", &[("accept-language", "<b>, *, es;q=0.5")]), "es");
+        assert_eq!(locale("/x?lang=a%20b", &[]), DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn accept_language_weights() {
+        let header = |v: &str| {
+            let mut h = HeaderMap::new();
+            h.insert(ACCEPT_LANGUAGE, v.parse().unwrap());
+            preferred_language(&h)
+        };
+        assert_eq!(header("en;q=0.5, nl;q=0.9, *;q=1").as_deref(), Some("nl"));
+        assert_eq!(header("en, fr").as_deref(), Some("en"));
+        assert_eq!(header("fr;q=0, it;q=bad").as_deref(), Some("it"));
+        assert_eq!(header("*"), None);
+    }
+
+    #[test]
+    fn cookies_are_split_trimmed_and_unquoted() {
+        let ctx = request(
+            "/x",
+            &[("cookie", "a=1; b = \"two\" ;flag; token=x=y"), ("cookie", "c=3")],
+        );
+        assert_eq!(ctx.cookie("a"), Some("1"));
+        assert_eq!(ctx.cookie("b"), Some("two"));
+        assert_eq!(ctx.cookie("token"), Some("x=y"));
+        assert_eq!(ctx.cookie("c"), Some("3"));
+        assert_eq!(ctx.cookie("flag"), None);
+        assert!(parse_cookies(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn language_tags() {
+        for ok in ["en", "pt-BR", "zh_Hant", "x1"] {
+            assert!(is_language_tag(ok), "{ok}");
+        }
+        for bad in ["", "en us", "<b>", "de\"", "a;b", &"a".repeat(36)] {
+            assert!(!is_language_tag(bad), "{bad}");
+        }
+        assert_eq!(request("/x?lang=de-AT", &[]).language(), "de");
+    }
+}
@@ -0,0 +1,393 @@
+use hyper::{
+    header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+        ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+        ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ALLOW, CONTENT_TYPE, HeaderValue, ORIGIN,
+        VARY,
+    },
+    Body, HeaderMap, Method, Response, StatusCode,
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Methods answered by `PluginComponents`, for `OPTIONS` requests that aren't preflights.
+const ALLOW_METHODS: &str = "GET, HEAD, POST, OPTIONS";
+
+/// Cross-origin access to `/components/**` (e.g. `cors.json`):
+/// { "allowedOrigins": ["https://smn.itch.io", "https://*.itch.zone"],
+///   "allowedMethods": ["GET", "POST"], "allowedHeaders": ["Content-Type"],
+///   "allowCredentials": false, "maxAge": 600 }
+/// No allowed origins (the default) means same-origin only: no CORS headers are sent and
+/// preflights are refused.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CorsConfig {
+    /// `scheme://host[:port]`; `*` for any origin, `scheme://*.domain` for its subdomains.
+    /// The opaque origin `null` only matches when listed explicitly.
+    pub allowed_origins: Vec<String>,
+    /// Methods a cross-origin request may use (`OPTIONS` is always answered)
+    pub allowed_methods: Vec<String>,
+    /// Request headers a cross-origin request may send; `*` allows any (not with credentials)
+    pub allowed_headers: Vec<String>,
+    /// Response headers readable by cross-origin scripts
+    pub expose_headers: Vec<String>,
+    /// Send cookies / auth with cross-origin requests (needs explicit origins, not `*`)
+    pub allow_credentials: bool,
+    /// Seconds a browser may cache a preflight answer
+    pub max_age: u32,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["GET".into(), "HEAD".into(), "POST".into()],
+            allowed_headers: vec!["Content-Type".into()],
+            // What componentManager.js reads from component responses
+            expose_headers: vec![
+                "Link".into(),
+                "X-Component-Error".into(),
+                "X-Component-Hot-Reload".into(),
+            ],
+            allow_credentials: false,
+            max_age: 600,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Same-origin only.
+    pub fn same_origin() -> Self {
+        Self::default()
+    }
+
+    /// Load from a JSON file; a missing file means same-origin only.
+    /// Invalid origins and credentials combined with wildcards are rejected.
+    pub fn from_config<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Ok(Self::same_origin());
+        }
+        let text = std::fs::read_to_string(path)?;
+        let config: CorsConfig = serde_json::from_str(&text)?;
+        config.validated()
+    }
+
+    /// Normalize origins (lowercase, no trailing `/`) and methods (uppercase), then check the rules.
+    pub fn validated(mut self) -> Result<Self, Error> {
+        self.allowed_origins = self
+            .allowed_origins
+            .iter()
+            .map(|o| o.trim().trim_end_matches('/').to_ascii_lowercase())
+            .collect();
+        self.allowed_methods = self.allowed_methods.iter().map(|m| m.trim().to_ascii_uppercase()).collect();
+
+        for origin in &self.allowed_origins {
+            if origin == "*" || origin == "null" {
+                continue;
+            }
+            let Some((scheme, host)) = origin.split_once("://") else {
+                return Err(Error::Invalid(format!("origin '{origin}' must look like scheme://host[:port]")));
+            };
+            let host = host.strip_prefix("*.").unwrap_or(host);
+            if scheme.is_empty() || host.is_empty() || host.contains(['/', '*', '?', '#', '@']) {
+                return Err(Error::Invalid(format!("origin '{origin}' must look like scheme://host[:port]")));
+            }
+        }
+        for method in &self.allowed_methods {
+            if Method::from_bytes(method.as_bytes()).is_err() {
+                return Err(Error::Invalid(format!("invalid method '{method}'")));
+            }
+        }
+        for name in self.allowed_headers.iter().chain(&self.expose_headers) {
+            if name != "*" && hyper::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(Error::Invalid(format!("invalid header name '{name}'")));
+            }
+        }
+        if self.allow_credentials {
+            if self.allowed_origins.iter().any(|o| o == "*" || o == "null") {
+                return Err(Error::Invalid("allowCredentials needs explicit origins, not '*' or 'null'".into()));
+            }
+            if self.allowed_headers.iter().any(|h| h == "*") {
+                return Err(Error::Invalid("allowCredentials needs explicit allowedHeaders, not '*'".into()));
+            }
+        }
+        Ok(self)
+    }
+
+    /// True when some cross-origin access is configured.
+    pub fn enabled(&self) -> bool {
+        !self.allowed_origins.is_empty()
+    }
+
+    /// Whether `origin` (the request's `Origin` header) may read responses.
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        let origin = origin.trim().to_ascii_lowercase();
+        self.allowed_origins.iter().any(|allowed| {
+            if allowed == "*" {
+                return origin != "null";
+            }
+            if let Some((scheme, suffix)) = allowed.split_once("://*.") {
+                // Subdomains only, same scheme: "https://*.itch.zone" matches "https://html.itch.zone"
+                return origin
+                    .strip_prefix(scheme)
+                    .and_then(|rest| rest.strip_prefix("://"))
+                    .and_then(|host| host.strip_suffix(suffix))
+                    .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.'));
+            }
+            *allowed == origin
+        })
+    }
+
+    fn allows_method(&self, method: &str) -> bool {
+        self.allowed_methods.iter().any(|m| m == method)
+    }
+
+    fn allows_header(&self, name: &str) -> bool {
+        self.allowed_headers.iter().any(|h| h == "*" || h.eq_ignore_ascii_case(name))
+    }
+
+    /// Answer an `OPTIONS` request: a CORS preflight is granted (204) or refused (403);
+    /// any other `OPTIONS` request gets the supported methods.
+    pub fn preflight(&self, headers: &HeaderMap) -> Response<Body> {
+        let origin = headers.get(ORIGIN).and_then(|v| v.to_str().ok());
+        let requested_method = headers.get(ACCESS_CONTROL_REQUEST_METHOD).and_then(|v| v.to_str().ok());
+        let (Some(origin), Some(requested_method)) = (origin, requested_method) else {
+            return Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .header(ALLOW, ALLOW_METHODS)
+                .body(Body::empty())
+                .unwrap();
+        };
+
+        let requested_headers: Vec<&str> = headers
+            .get_all(ACCESS_CONTROL_REQUEST_HEADERS)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+            .collect();
+
+        let refusal = if !self.allows_origin(origin) {
+            Some("origin not allowed")
+        } else if !self.allows_method(requested_method.trim()) {
+            Some("method not allowed")
+        } else if !requested_headers.iter().all(|h| self.allows_header(h)) {
+            Some("header not allowed")
+        } else {
+            None
+        };
+        if let Some(reason) = refusal {
+            return Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+                .header(VARY, "Origin, Access-Control-Request-Method, Access-Control-Request-Headers")
+                .body(Body::from(format!("403 Forbidden: CORS {reason}")))
+                .unwrap();
+        }
+
+        let mut response = Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header(VARY, "Origin, Access-Control-Request-Method, Access-Control-Request-Headers")
+            .header(ACCESS_CONTROL_ALLOW_METHODS, self.allowed_methods.join(", "))
+            .header(ACCESS_CONTROL_MAX_AGE, self.max_age.to_string())
+            .body(Body::empty())
+            .unwrap();
+        let out = response.headers_mut();
+        if let Ok(v) = HeaderValue::from_str(origin) {
+            out.insert(ACCESS_CONTROL_ALLOW_ORIGIN, v);
+        }
+        // Echo what was asked for: `*` in the config, or a subset of the allowed list
+        if !requested_headers.is_empty()
+            && let Ok(v) = HeaderValue::from_str(&requested_headers.join(", "))
+        {
+            out.insert(ACCESS_CONTROL_ALLOW_HEADERS, v);
+        }
+        if self.allow_credentials {
+            out.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        response
+    }
+
+    /// Add the CORS headers for a request from `origin` to a response.
+    /// Responses vary by `Origin` whenever cross-origin access is configured.
+    pub fn apply(&self, origin: Option<&HeaderValue>, response: &mut Response<Body>) {
+        if !self.enabled() {
+            return;
+        }
+        let headers = response.headers_mut();
+        headers.append(VARY, HeaderValue::from_static("Origin"));
+
+        let Some(origin) = origin.filter(|o| o.to_str().is_ok_and(|o| self.allows_origin(o))) else {
+            return;
+        };
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+        if self.allow_credentials {
+            headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        if !self.expose_headers.is_empty()
+            && let Ok(v) = HeaderValue::from_str(&self.expose_headers.join(", "))
+        {
+            headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, v);
+        }
+    }
+}
+
+// ---------------------- Errors ----------------------
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+}
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self { Error::Io(e) }
+}
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self { Error::Json(e) }
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Json(e) => write!(f, "JSON parse error: {e}"),
+            Error::Invalid(msg) => write!(f, "Invalid CORS config: {msg}"),
+        }
+    }
+}
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(origins: &[&str]) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            ..CorsConfig::default()
+        }
+        .validated()
+        .expect("valid config")
+    }
+
+    fn preflight_headers(origin: &str, method: &str, request_headers: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ORIGIN, origin.parse().unwrap());
+        headers.insert(ACCESS_CONTROL_REQUEST_METHOD, method.parse().unwrap());
+        if let Some(h) = request_headers {
+            headers.insert(ACCESS_CONTROL_REQUEST_HEADERS, h.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn subdomain_wildcard_matches_subdomains_only() {
+        let cors = config(&["https://*.itch.zone"]);
+        assert!(cors.allows_origin("https://html.itch.zone"));
+        assert!(cors.allows_origin("https://a.b.itch.zone"));
+        assert!(cors.allows_origin("HTTPS://HTML.ITCH.ZONE"));
+        assert!(!cors.allows_origin("https://evilitch.zone"));
+        assert!(!cors.allows_origin("http://html.itch.zone"));
+        assert!(!cors.allows_origin("https://itch.zone"));
+        assert!(!cors.allows_origin("https://.itch.zone"));
+        assert!(!cors.allows_origin("https://html.itch.zone.evil.com"));
+    }
+
+    #[test]
+    fn exact_origins_and_null() {
+        let cors = config(&["https://smn.itch.io/"]);
+        assert!(cors.allows_origin("https://smn.itch.io"));
+        assert!(!cors.allows_origin("https://smn.itch.io:8443"));
+
+        let any = config(&["*"]);
+        assert!(any.allows_origin("https://example.com"));
+        assert!(!any.allows_origin("null"));
+        assert!(config(&["null"]).allows_origin("null"));
+    }
+
+    #[test]
+    fn validated_rejects_bad_configs() {
+        let invalid = |c: CorsConfig| matches!(c.validated(), Err(Error::Invalid(_)));
+        let with = |origins: &[&str], credentials: bool| CorsConfig {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            allow_credentials: credentials,
+            ..CorsConfig::default()
+        };
+        assert!(invalid(with(&["*"], true)));
+        assert!(invalid(with(&["null"], true)));
+        assert!(invalid(CorsConfig { allowed_headers: vec!["*".into()], ..with(&["https://a.io"], true) }));
+        assert!(with(&["https://a.io"], true).validated().is_ok());
+        assert!(with(&["*"], false).validated().is_ok());
+
+        assert!(invalid(with(&["itch.zone"], false)));
+        assert!(invalid(with(&["https://a.io/path"], false)));
+        assert!(invalid(with(&["https://*"], false)));
+        assert!(invalid(CorsConfig { allowed_methods: vec!["GE T".into()], ..with(&["*"], false) }));
+        assert!(invalid(CorsConfig { expose_headers: vec!["Bad Header".into()], ..with(&["*"], false) }));
+    }
+
+    #[test]
+    fn preflight_grants_allowed_requests() {
+        let cors = config(&["https://*.itch.zone"]);
+        let resp = cors.preflight(&preflight_headers("https://html.itch.zone", "POST", Some("content-type")));
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let h = resp.headers();
+        assert_eq!(h[ACCESS_CONTROL_ALLOW_ORIGIN], "https://html.itch.zone");
+        assert_eq!(h[ACCESS_CONTROL_ALLOW_METHODS], "GET, HEAD, POST");
+        assert_eq!(h[ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+        assert_eq!(h[ACCESS_CONTROL_MAX_AGE], "600");
+        assert!(h[VARY].to_str().unwrap().contains("Origin"));
+        assert!(h.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).is_none());
+    }
+
+    #[test]
+    fn preflight_refuses_disallowed_origin_method_or_header() {
+        let cors = config(&["https://*.itch.zone"]);
+        for headers in [
+            preflight_headers("https://evilitch.zone", "GET", None),
+            preflight_headers("https://html.itch.zone", "DELETE", None),
+            preflight_headers("https://html.itch.zone", "POST", Some("Content-Type, X-Secret")),
+        ] {
+            let resp = cors.preflight(&headers);
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+            assert!(resp.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        }
+
+        // A plain OPTIONS request just lists the methods
+        let resp = cors.preflight(&HeaderMap::new());
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(resp.headers()[ALLOW], ALLOW_METHODS);
+    }
+
+    #[test]
+    fn no_config_means_same_origin_only() {
+        let cors = CorsConfig::from_config("does/not/exist/cors.json").unwrap();
+        assert!(!cors.enabled());
+        assert!(!cors.allows_origin("https://example.com"));
+
+        let resp = cors.preflight(&preflight_headers("https://example.com", "GET", None));
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let mut resp = Response::new(Body::empty());
+        cors.apply(Some(&HeaderValue::from_static("https://example.com")), &mut resp);
+        assert!(resp.headers().is_empty());
+    }
+
+    #[test]
+    fn apply_echoes_allowed_origins_and_varies() {
+        let cors = config(&["https://smn.itch.io"]);
+        let mut resp = Response::new(Body::empty());
+        cors.apply(Some(&HeaderValue::from_static("https://smn.itch.io")), &mut resp);
+        let h = resp.headers();
+        assert_eq!(h[ACCESS_CONTROL_ALLOW_ORIGIN], "https://smn.itch.io");
+        assert_eq!(h[VARY], "Origin");
+        assert!(h[ACCESS_CONTROL_EXPOSE_HEADERS].to_str().unwrap().contains("X-Component-Error"));
+
+        let mut resp = Response::new(Body::empty());
+        cors.apply(Some(&HeaderValue::from_static("https://other.io")), &mut resp);
+        assert_eq!(resp.headers()[VARY], "Origin");
+        assert!(resp.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::plugins::plugin_components::ComponentProps;
+
+/// What a component does and which arguments it takes; served by `/components/_index`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComponentDescription {
+    pub description: String,
+    /// In positional (`compArgs`) order; each is also addressable by name (`compProps`)
+    pub args: Vec<ArgSpec>,
+    /// Arguments that render a representative instance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<ComponentExample>,
+}
+
+/// One declared argument.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArgSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ArgKind,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+impl ArgSpec {
+    /// Optional argument of type `kind` without a default.
+    pub fn new(name: &str, kind: ArgKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            required: false,
+            default: None,
+            description: String::new(),
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn default_value(mut self, default: impl Into<Value>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    pub fn describe(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgKind {
+    #[default]
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+}
+
+/// `{ "compArgs": [...], "compProps": {...} }`, the same shape as a component request body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ComponentExample {
+    #[serde(default)]
+    pub compArgs: Vec<String>,
+    #[serde(default)]
+    pub compProps: Map<String, Value>,
+}
+
+impl ComponentDescription {
+    /// Check request arguments against the declared ones.
+    /// Errors list every problem, e.g. `"count": expected number, got "abc"`.
+    /// Components that declare no arguments (plain templates) accept and ignore any.
+    pub fn validate(&self, args: &[String], props: &ComponentProps) -> Result<(), Vec<String>> {
+        if self.args.is_empty() {
+            return Ok(());
+        }
+        let mut problems = Vec::new();
+
+        if args.len() > self.args.len() {
+            problems.push(format!(
+                "expected at most {} positional arg(s) [{}], got {}",
+                self.args.len(),
+                self.arg_names().join(", "),
+                args.len()
+            ));
+        }
+        for name in props.as_map().keys() {
+            if !self.args.iter().any(|a| &a.name == name) {
+                problems.push(format!(
+                    "unknown prop \"{name}\" (expected one of: {})",
+                    self.arg_names().join(", ")
+                ));
+            }
+        }
+
+        for (i, spec) in self.args.iter().enumerate() {
+            let problem = match (props.get(&spec.name), args.get(i)) {
+                (Some(value), _) => check_value(spec.kind, value),
+                (None, Some(raw)) => check_value(spec.kind, &Value::String(raw.clone())),
+                (None, None) if spec.required => Some("required but missing".to_string()),
+                (None, None) => None,
+            };
+            if let Some(p) = problem {
+                problems.push(format!("\"{}\": {p}", spec.name));
+            }
+        }
+
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+
+    fn arg_names(&self) -> Vec<&str> {
+        self.args.iter().map(|a| a.name.as_str()).collect()
+    }
+}
+
+/// Whether `value` can be read as `kind`. Strings are accepted for scalars because
+/// positional args and query parameters are always strings.
+fn check_value(kind: ArgKind, value: &Value) -> Option<String> {
+    let ok = match (kind, value) {
+        (ArgKind::String, Value::String(_) | Value::Number(_) | Value::Bool(_)) => true,
+        (ArgKind::Number, Value::Number(_)) => true,
+        (ArgKind::Number, Value::String(s)) => s.trim().parse::<f64>().is_ok(),
+        (ArgKind::Bool, Value::Bool(_)) => true,
+        (ArgKind::Bool, Value::String(s)) => matches!(s.trim(), "true" | "false" | "1" | "0"),
+        (ArgKind::Array, _) => true, // scalars are read as one-element arrays
+        (ArgKind::Object, Value::Object(_)) => true,
+        _ => false,
+    };
+    (!ok).then(|| format!("expected {}, got {value}", kind_name(kind)))
+}
+
+fn kind_name(kind: ArgKind) -> &'static str {
+    match kind {
+        ArgKind::String => "string",
+        ArgKind::Number => "number",
+        ArgKind::Bool => "bool",
+        ArgKind::Array => "array",
+        ArgKind::Object => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn describe(args: Vec<ArgSpec>) -> ComponentDescription {
+        ComponentDescription { args, ..Default::default() }
+    }
+
+    fn props(value: Value) -> ComponentProps {
+        match value {
+            Value::Object(map) => ComponentProps::new(map),
+            _ => ComponentProps::default(),
+        }
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn card() -> ComponentDescription {
+        describe(vec![
+            ArgSpec::new("project", ArgKind::String).required(),
+            ArgSpec::new("limit", ArgKind::Number),
+            ArgSpec::new("open", ArgKind::Bool).default_value(false),
+        ])
+    }
+
+    #[test]
+    fn required_args_from_position_or_prop() {
+        let d = card();
+        assert!(d.validate(&strings(&["a"]), &props(json!({}))).is_ok());
+        assert!(d.validate(&[], &props(json!({ "project": "a" }))).is_ok());
+        assert_eq!(
+            d.validate(&[], &props(json!({}))),
+            Err(vec![r#""project": required but missing"#.to_string()])
+        );
+    }
+
+    #[test]
+    fn optional_args_are_type_checked_when_present() {
+        let d = card();
+        assert!(d.validate(&strings(&["a", "3", "true"]), &props(json!({}))).is_ok());
+        assert!(d.validate(&strings(&["a"]), &props(json!({ "limit": 2, "open": "0" }))).is_ok());
+        assert_eq!(
+            d.validate(&strings(&["a", "abc"]), &props(json!({ "open": "yes" }))),
+            Err(vec![
+                r#""limit": expected number, got "abc""#.to_string(),
+                r#""open": expected bool, got "yes""#.to_string(),
+            ])
+        );
+        // A prop overrides the positional arg at the same index
+        assert!(d.validate(&strings(&["a", "abc"]), &props(json!({ "limit": 1 }))).is_ok());
+    }
+
+    #[test]
+    fn extra_args_and_unknown_props_are_rejected() {
+        let d = card();
+        let problems = d
+            .validate(&strings(&["a", "1", "true", "x"]), &props(json!({ "colour": "red" })))
+            .unwrap_err();
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].starts_with("expected at most 3 positional arg(s) [project, limit, open], got 4"));
+        assert!(problems[1].starts_with(r#"unknown prop "colour""#));
+    }
+
+    #[test]
+    fn argless_components_ignore_args() {
+        let d = describe(Vec::new());
+        assert!(d.validate(&strings(&["anything", "else"]), &props(json!({ "x": [1] }))).is_ok());
+    }
+
+    #[test]
+    fn check_value_kinds() {
+        assert!(check_value(ArgKind::String, &json!(3)).is_none());
+        assert!(check_value(ArgKind::String, &json!([1])).is_some());
+        assert!(check_value(ArgKind::Number, &json!(" 2.5 ")).is_none());
+        assert!(check_value(ArgKind::Array, &json!("one")).is_none());
+        assert!(check_value(ArgKind::Object, &json!({})).is_none());
+        assert!(check_value(ArgKind::Object, &json!("{}")).is_some());
+    }
+}
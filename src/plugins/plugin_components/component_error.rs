@@ -0,0 +1,222 @@
+use hyper::{
+    Body, Response, StatusCode,
+    header::{CACHE_CONTROL, CONTENT_TYPE, HeaderValue},
+};
+use serde::Serialize;
+use std::fmt;
+
+use crate::plugins::plugin_components::html_escape;
+
+/// Response header naming the error kind, on both problem details and dev fragments.
+pub const ERROR_HEADER: &str = "x-component-error";
+
+/// Why a component could not be rendered.
+#[derive(Debug)]
+pub enum ComponentError {
+    /// Unknown component, or data it refers to (e.g. a project) doesn't exist. 404
+    NotFound(String),
+    /// Request arguments don't match what the component expects; one entry per problem. 400
+    BadArgs(Vec<String>),
+    /// The component's template file is missing. Holds where it was expected. 500
+    TemplateMissing(String),
+    /// The handler or a nested include failed. 500
+    Render(String),
+    /// Reading a file failed. 500
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for ComponentError {
+    fn from(e: std::io::Error) -> Self {
+        ComponentError::Io(e)
+    }
+}
+
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentError::NotFound(what) => write!(f, "not found: {what}"),
+            ComponentError::BadArgs(problems) => write!(f, "invalid arguments: {}", problems.join("; ")),
+            ComponentError::TemplateMissing(path) => write!(f, "template not found: expected {path}"),
+            ComponentError::Render(e) => write!(f, "render failed: {e}"),
+            ComponentError::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+impl std::error::Error for ComponentError {}
+
+/// RFC 9457 problem details body (`application/problem+json`).
+#[derive(Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: &'static str,
+    pub status: u16,
+    /// Absent for server errors outside development mode (their text may name files)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub component: String,
+    /// Individual argument problems (bad args only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl ComponentError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ComponentError::NotFound(_) => StatusCode::NOT_FOUND,
+            ComponentError::BadArgs(_) => StatusCode::BAD_REQUEST,
+            ComponentError::TemplateMissing(_) | ComponentError::Render(_) | ComponentError::Io(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// Short machine-readable kind, e.g. "bad-args".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ComponentError::NotFound(_) => "not-found",
+            ComponentError::BadArgs(_) => "bad-args",
+            ComponentError::TemplateMissing(_) => "template-missing",
+            ComponentError::Render(_) => "render-failed",
+            ComponentError::Io(_) => "io",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ComponentError::NotFound(_) => "Component or data not found",
+            ComponentError::BadArgs(_) => "Invalid component arguments",
+            ComponentError::TemplateMissing(_) => "Component template missing",
+            ComponentError::Render(_) => "Component render failed",
+            ComponentError::Io(_) => "Component I/O error",
+        }
+    }
+
+    /// Problem details for `component`. `verbose` (development mode) keeps the error text of
+    /// server errors, which can contain filesystem paths; otherwise they only get title and status.
+    pub fn problem(&self, component: &str, verbose: bool) -> Problem {
+        let (detail, errors) = match self {
+            ComponentError::BadArgs(problems) => (
+                Some("See /components/_index for the expected arguments.".to_string()),
+                problems.clone(),
+            ),
+            other if verbose || !other.status().is_server_error() => (Some(other.to_string()), Vec::new()),
+            _ => (None, Vec::new()),
+        };
+        Problem {
+            kind: format!("urn:smn-component:{}", self.kind()),
+            title: self.title(),
+            status: self.status().as_u16(),
+            detail,
+            component: component.to_string(),
+            errors,
+        }
+    }
+
+    /// Visible error box for development mode, mounted in place of the broken component.
+    pub fn html_fragment(&self, component: &str) -> String {
+        let problem = self.problem(component, true);
+        let items: String = problem
+            .errors
+            .iter()
+            .map(|e| format!("<li>{}</li>", html_escape(e)))
+            .collect();
+        let list = if items.is_empty() {
+            String::new()
+        } else {
+            format!(r#"<ul style="margin:.5em 0 0 1.2em;">{items}</ul>"#)
+        };
+        format!(
+            r#"<div class="smn-component-error" role="alert" data-component="{name}" data-error="{kind}" style="border:2px dashed #b44a4a;background:rgba(180,74,74,.15);color:#fff;padding:.75em 1em;margin:.5em 0;border-radius:8px;font:14px/1.4 monospace;text-align:left;"><strong>&lt;{name}&gt; {status}: {title}</strong><div>{detail}</div>{list}</div>"#,
+            name = html_escape(component),
+            kind = self.kind(),
+            status = problem.status,
+            title = html_escape(problem.title),
+            detail = html_escape(problem.detail.as_deref().unwrap_or_default()),
+        )
+    }
+
+    /// Problem details JSON, or the HTML fragment in development mode. Never cached.
+    pub fn into_response(self, component: &str, dev_mode: bool) -> Response<Body> {
+        let (body, content_type) = if dev_mode {
+            (self.html_fragment(component), "text/html; charset=utf-8")
+        } else {
+            let json = serde_json::to_string(&self.problem(component, false)).unwrap_or_else(|_| "{}".to_string());
+            (json, "application/problem+json")
+        };
+        let mut resp = Response::new(Body::from(body));
+        *resp.status_mut() = self.status();
+        let headers = resp.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        headers.insert(ERROR_HEADER, HeaderValue::from_static(self.kind()));
+        resp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "/srv/site/components/card/template.html";
+
+    fn server_errors() -> Vec<ComponentError> {
+        vec![
+            ComponentError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, format!("{PATH}: no such file"))),
+            ComponentError::TemplateMissing(PATH.to_string()),
+            ComponentError::Render(format!("failed to load project structure: {PATH}")),
+        ]
+    }
+
+    async fn body_text(resp: Response<Body>) -> String {
+        let bytes = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn production_problems_never_contain_paths() {
+        for e in server_errors() {
+            let json = serde_json::to_string(&e.problem("card", false)).unwrap();
+            assert!(!json.contains("/srv/"), "{json}");
+            assert!(!json.contains("detail"), "{json}");
+
+            let (kind, title) = (e.kind(), e.title());
+            let resp = e.into_response("card", false);
+            assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(resp.headers()[CONTENT_TYPE], "application/problem+json");
+            let body: serde_json::Value = serde_json::from_str(&body_text(resp).await).unwrap();
+            assert_eq!(
+                body,
+                serde_json::json!({
+                    "type": format!("urn:smn-component:{kind}"),
+                    "title": title,
+                    "status": 500,
+                    "component": "card",
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn dev_mode_keeps_the_full_text() {
+        for e in server_errors() {
+            assert!(e.problem("card", true).detail.unwrap().contains(PATH));
+            assert!(e.html_fragment("card").contains(PATH));
+            let resp = e.into_response("card", true);
+            assert_eq!(resp.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+            assert!(body_text(resp).await.contains(PATH));
+        }
+    }
+
+    #[test]
+    fn client_errors_keep_their_detail() {
+        let problem = ComponentError::NotFound("project \"x\"".to_string()).problem("card", false);
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.detail.as_deref(), Some("not found: project \"x\""));
+
+        let problem = ComponentError::BadArgs(vec!["\"n\": required but missing".to_string()]).problem("card", false);
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.errors, ["\"n\": required but missing"]);
+        assert!(problem.detail.unwrap().contains("/components/_index"));
+    }
+}
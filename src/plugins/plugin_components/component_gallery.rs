@@ -0,0 +1,217 @@
+use hyper::{
+    Body, Response,
+    header::{CONTENT_TYPE, HeaderValue},
+};
+use std::sync::Arc;
+
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentDescription, ComponentError, ComponentOutput, ComponentProps, component_describe::ComponentExample,
+    component_include::ComponentIncludes, html_escape, run_component,
+};
+
+/// `/components/_gallery`: every registered component rendered with its example arguments,
+/// next to its raw HTML output and a form for trying other arguments live.
+pub(crate) async fn gallery_response(includes: &ComponentIncludes, ctx: &ComponentContext) -> Response<Body> {
+    // Snapshot first: rendering must not hold the registry lock
+    let components: Vec<(&'static str, String, Option<Arc<ComponentDescription>>)> = {
+        let registry = includes.registry().read().expect("component registry lock poisoned");
+        registry
+            .list()
+            .into_iter()
+            .map(|(name, source)| (name, source.to_string(), registry.description(name)))
+            .collect()
+    };
+
+    let mut sections = String::new();
+    let mut nav = String::new();
+    for (name, source, description) in &components {
+        let example = description
+            .as_ref()
+            .and_then(|d| d.example.clone())
+            .unwrap_or_default();
+        let output = run_component(
+            includes,
+            ctx,
+            name,
+            example.compArgs.clone(),
+            ComponentProps::new(example.compProps.clone()),
+        )
+        .await;
+
+        nav.push_str(&format!(
+            "<li><a href=\"#c-{id}\">{id}</a></li>",
+            id = html_escape(name)
+        ));
+        sections.push_str(&render_section(
+            name,
+            source,
+            description.as_deref(),
+            &example,
+            &output,
+        ));
+    }
+
+    // One pass: component output inside `sections` is never scanned for placeholders
+    let html = format!(
+        "{GALLERY_HEAD}  <p class=\"g-muted\">{count} registered component(s) · <a href=\"/components/_index\">/components/_index</a></p>\n  <ul class=\"g-nav\">{nav}</ul>\n  {sections}\n{GALLERY_SCRIPT}",
+        count = components.len(),
+    );
+
+    let mut resp = Response::new(Body::from(html));
+    resp.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    resp
+}
+
+fn render_section(
+    name: &str,
+    source: &str,
+    description: Option<&ComponentDescription>,
+    example: &ComponentExample,
+    output: &Result<ComponentOutput, ComponentError>,
+) -> String {
+    let name_esc = html_escape(name);
+    let summary = description
+        .map(|d| html_escape(&d.description))
+        .unwrap_or_else(|| "<em>No description (handler does not implement describe())</em>".to_string());
+
+    let args = match description {
+        Some(d) if !d.args.is_empty() => {
+            let rows: String = d
+                .args
+                .iter()
+                .map(|a| {
+                    format!(
+                        "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        html_escape(&a.name),
+                        serde_json::to_value(a.kind)
+                            .ok()
+                            .and_then(|v| v.as_str().map(str::to_string))
+                            .unwrap_or_default(),
+                        if a.required { "yes" } else { "" },
+                        a.default
+                            .as_ref()
+                            .map(|v| format!("<code>{}</code>", html_escape(&v.to_string())))
+                            .unwrap_or_default(),
+                        html_escape(&a.description),
+                    )
+                })
+                .collect();
+            format!(
+                "<table class=\"g-args\"><tr><th>Arg</th><th>Type</th><th>Required</th><th>Default</th><th>Description</th></tr>{rows}</table>"
+            )
+        }
+        Some(_) => "<p class=\"g-muted\">No arguments.</p>".to_string(),
+        None => String::new(),
+    };
+
+    let payload = serde_json::to_string_pretty(example).unwrap_or_else(|_| "{}".to_string());
+    // The preview gets the component's own assets; failures show their error box and problem JSON
+    let (status, ok, preview, raw) = match output {
+        Ok(out) => (200, true, format!("{}{}", out.head_tags(""), out.html), out.html.clone()),
+        Err(e) => (
+            e.status().as_u16(),
+            false,
+            e.html_fragment(name),
+            serde_json::to_string_pretty(&e.problem(name, true)).unwrap_or_default(),
+        ),
+    };
+
+    format!(
+        r#"<section class="g-component" id="c-{name_esc}" data-name="{name_esc}">
+  <h2>{name_esc} <span class="g-muted">({source})</span></h2>
+  <p>{summary}</p>
+  {args}
+  <div class="g-columns">
+    <div>
+      <h3>Preview <span class="g-status" data-ok="{ok}">{status}</span></h3>
+      <div class="g-preview">{preview}</div>
+    </div>
+    <div>
+      <h3>Output</h3>
+      <pre class="g-output">{output}</pre>
+      <form class="g-try">
+        <label>Request body (<code>compArgs</code> / <code>compProps</code>)</label>
+        <textarea rows="6" spellcheck="false">{payload}</textarea>
+        <button type="submit">Render</button>
+      </form>
+    </div>
+  </div>
+</section>
+"#,
+        source = html_escape(source),
+        output = html_escape(&raw),
+        payload = html_escape(&payload),
+    )
+}
+
+/// Gallery page up to the opening `<body>` content.
+const GALLERY_HEAD: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+  <title>Component gallery</title>
+  <link rel="stylesheet" href="/styles/styles-base.css" />
+  <link rel="stylesheet" href="/styles/styles-elements.css" />
+  <style>
+    body { padding: 2rem; }
+    .g-nav { display: flex; flex-wrap: wrap; gap: .25rem 1rem; list-style: none; padding: 0; }
+    .g-component { margin: 2rem 0; padding-top: 1rem; border-top: 1px solid rgba(255,255,255,.2); }
+    .g-columns { display: grid; grid-template-columns: 1fr 1fr; gap: 1.5rem; }
+    .g-preview { border: 1px dashed rgba(255,255,255,.3); padding: 1rem; min-height: 4rem; }
+    .g-output { max-height: 20rem; overflow: auto; white-space: pre-wrap; font-size: .8rem; background: rgba(0,0,0,.4); padding: .5rem; }
+    .g-try textarea { width: 100%; font-family: monospace; }
+    .g-args { border-collapse: collapse; margin: .5rem 0; }
+    .g-args td, .g-args th { padding: .2rem .6rem; text-align: left; }
+    .g-muted { opacity: .6; font-size: .9em; }
+    .g-status[data-ok="false"] { color: #f66; }
+    @media (max-width: 800px) { .g-columns { grid-template-columns: 1fr; } }
+  </style>
+</head>
+<body>
+  <h1>Component gallery</h1>
+"#;
+
+/// "Try it" script and the end of the gallery page.
+const GALLERY_SCRIPT: &str = r#"  <script>
+    document.querySelectorAll('.g-component').forEach(section => {
+      const name = section.dataset.name;
+      const form = section.querySelector('.g-try');
+      form.addEventListener('submit', async (ev) => {
+        ev.preventDefault();
+        const status = section.querySelector('.g-status');
+        const output = section.querySelector('.g-output');
+        const preview = section.querySelector('.g-preview');
+        let body;
+        try {
+          body = JSON.stringify(JSON.parse(form.querySelector('textarea').value || '{}'));
+        } catch (err) {
+          status.textContent = 'invalid JSON';
+          status.dataset.ok = 'false';
+          return;
+        }
+        const res = await fetch('/components/' + encodeURIComponent(name), {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body,
+        });
+        const text = await res.text();
+        status.textContent = res.status;
+        status.dataset.ok = String(res.ok);
+        output.textContent = text;
+        // Component stylesheets arrive as Link headers
+        const links = (res.headers.get('Link') || '').split(/,(?=\s*<)/)
+          .map(part => part.match(/<([^>]*)>.*rel="stylesheet"/))
+          .filter(Boolean)
+          .map(m => '<link rel="stylesheet" href="' + m[1] + '" />')
+          .join('');
+        preview.innerHTML = res.ok ? links + text : (res.headers.get('Content-Type') || '').startsWith('text/html') ? text : '';
+      });
+    });
+  </script>
+</body>
+</html>
+"#;
@@ -0,0 +1,488 @@
+use hyper::{Body, Request};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use smn_web_core::structs::struct_plugin::PluginContext;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentError, ComponentOutput, ComponentProps,
+    component_registry::ComponentRegistry, render_component,
+};
+
+/// Opening tag of an include marker, used both in static pages and inside component templates:
+/// `<smn-component name="header" args='["Game Design"]' props='{"k": "v"}'></smn-component>`
+/// (self-closing `<smn-component name="x"/>` works too).
+const MARKER_OPEN: &str = "<smn-component";
+const MARKER_CLOSE: &str = "</smn-component>";
+
+/// Development mode: expanded components sit between these comments, so `componentManager.js`
+/// can find and re-mount them when their template changes (hot reload):
+/// `<!--smn-component:begin {"name":"header","args":[],"props":{}}-->…<!--smn-component:end-->`
+const SLOT_BEGIN: &str = "<!--smn-component:begin ";
+const SLOT_END: &str = "<!--smn-component:end-->";
+
+/// How deep component templates may nest other components.
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Why a component tree could not be expanded.
+#[derive(Debug)]
+pub enum IncludeError {
+    /// A component includes itself, directly or indirectly. Holds the chain, e.g. [a, b, a].
+    Cycle(Vec<String>),
+    /// Nesting went past `MAX_INCLUDE_DEPTH`. Holds the chain up to that point.
+    TooDeep(Vec<String>),
+    /// The component is unknown or its handler failed.
+    Render(String, ComponentError),
+}
+impl fmt::Display for IncludeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IncludeError::Cycle(chain) => write!(f, "component include cycle: {}", chain.join(" -> ")),
+            IncludeError::TooDeep(chain) => write!(
+                f,
+                "component include depth limit ({MAX_INCLUDE_DEPTH}) exceeded: {}",
+                chain.join(" -> ")
+            ),
+            IncludeError::Render(name, e) => write!(f, "component \"{name}\": {e}"),
+        }
+    }
+}
+impl std::error::Error for IncludeError {}
+
+/// Expands `<smn-component>` markers in served HTML by running the registered components.
+/// Cloneable handle onto `PluginComponents`' registry, so other plugins can use it.
+#[derive(Clone)]
+pub struct ComponentIncludes {
+    handlers: Arc<RwLock<ComponentRegistry>>,
+    dev_mode: bool,
+}
+
+impl ComponentIncludes {
+    pub fn new(handlers: Arc<RwLock<ComponentRegistry>>, dev_mode: bool) -> Self {
+        Self { handlers, dev_mode }
+    }
+
+    /// Component context for rendering includes while handling `req`.
+    pub fn context(&self, req: &Request<Body>, plugin: &PluginContext) -> ComponentContext {
+        ComponentContext::from_request(req, plugin, self.dev_mode)
+    }
+
+    /// The component registry this handle renders from.
+    pub fn registry(&self) -> &RwLock<ComponentRegistry> {
+        &self.handlers
+    }
+
+    /// Whether `html` contains anything to expand (cheap pre-check before buffering work).
+    pub fn has_markers(html: &str) -> bool {
+        html.contains(MARKER_OPEN)
+    }
+
+    /// Replace every marker in a page with its component's HTML (nested components included)
+    /// and add the stylesheets / scripts they need to the page `<head>`.
+    /// Markers that fail to render are left in place so `componentManager.js` can still mount them;
+    /// a cycle or runaway nesting is logged and replaced by an HTML comment naming the chain.
+    /// In development mode both become a visible error fragment instead, and every expanded
+    /// component is wrapped in slot comments for hot reload.
+    pub async fn expand(&self, ctx: &ComponentContext, html: &str) -> String {
+        match self.expand_markers(ctx, html, Vec::new(), None, false).await {
+            Ok(out) => inject_head_tags(&out.html, &out.head_tags(&out.html)),
+            // Non-strict expansion reports cycles inline and never fails.
+            Err(e) => {
+                eprintln!("WARN: Server-side include failed: {e}");
+                html.to_string()
+            }
+        }
+    }
+
+    /// Expand the child markers inside the output of `component_name`.
+    /// Children without their own `args` / `props` attributes inherit the parent's.
+    /// The result carries the expanded HTML plus the children's assets and cache hints.
+    pub async fn expand_children(
+        &self,
+        ctx: &ComponentContext,
+        component_name: &str,
+        html: &str,
+        args: &[String],
+        props: &ComponentProps,
+    ) -> Result<ComponentOutput, IncludeError> {
+        let parent = (args.to_vec(), props.clone());
+        self.expand_markers(ctx, html, vec![component_name.to_string()], Some(&parent), true)
+            .await
+    }
+
+    /// Render `name` and, recursively, everything it includes.
+    /// `chain` lists the components currently being rendered, outermost first.
+    fn render_tree<'a>(
+        &'a self,
+        ctx: &'a ComponentContext,
+        name: &'a str,
+        args: Vec<String>,
+        props: ComponentProps,
+        chain: Vec<String>,
+    ) -> BoxFuture<'a, Result<ComponentOutput, IncludeError>> {
+        Box::pin(async move {
+            let mut chain = chain;
+            let cyclic = chain.iter().any(|c| c == name);
+            chain.push(name.to_string());
+            if cyclic {
+                return Err(IncludeError::Cycle(chain));
+            }
+            if chain.len() > MAX_INCLUDE_DEPTH {
+                return Err(IncludeError::TooDeep(chain));
+            }
+
+            let mut output = render_component(&self.handlers, ctx, name, args.clone(), props.clone())
+                .await
+                .map_err(|e| IncludeError::Render(name.to_string(), e))?;
+            if !Self::has_markers(&output.html) {
+                return Ok(output);
+            }
+            let children = self
+                .expand_markers(ctx, &output.html, chain, Some(&(args, props)), true)
+                .await?;
+            output.html = children.html.clone();
+            output.absorb_assets(&children);
+            Ok(output)
+        })
+    }
+
+    /// Replace the markers in `html`. Unrenderable components keep their marker.
+    /// `strict`: propagate cycle / depth errors (nested) instead of reporting them inline (page).
+    fn expand_markers<'a>(
+        &'a self,
+        ctx: &'a ComponentContext,
+        html: &'a str,
+        chain: Vec<String>,
+        parent: Option<&'a (Vec<String>, ComponentProps)>,
+        strict: bool,
+    ) -> BoxFuture<'a, Result<ComponentOutput, IncludeError>> {
+        Box::pin(async move {
+            let mut out = String::with_capacity(html.len());
+            let mut assets = ComponentOutput::html(String::new());
+            let mut rest = html;
+
+            while let Some(marker) = find_marker(rest) {
+                out.push_str(&rest[..marker.start]);
+                let original = &rest[marker.start..marker.end];
+
+                let args = marker
+                    .args
+                    .or_else(|| parent.map(|p| p.0.clone()))
+                    .unwrap_or_default();
+                let props = marker
+                    .props
+                    .or_else(|| parent.map(|p| p.1.clone()))
+                    .unwrap_or_default();
+
+                let slot = ctx.dev_mode.then(|| slot_begin(&marker.name, &args, &props));
+                match self.render_tree(ctx, &marker.name, args, props, chain.clone()).await {
+                    Ok(rendered) => {
+                        match slot {
+                            Some(begin) => out.push_str(&format!("{begin}{}{SLOT_END}", rendered.html)),
+                            None => out.push_str(&rendered.html),
+                        }
+                        assets.absorb_assets(&rendered);
+                    }
+                    Err(IncludeError::Render(name, e)) => {
+                        eprintln!("WARN: Server-side include of '{name}' failed: {e}");
+                        if ctx.dev_mode {
+                            out.push_str(&e.html_fragment(&name));
+                        } else {
+                            out.push_str(original);
+                        }
+                    }
+                    Err(e) if strict => return Err(e),
+                    Err(e) => {
+                        eprintln!("WARN: Server-side include failed: {e}");
+                        if ctx.dev_mode {
+                            out.push_str(&ComponentError::Render(e.to_string()).html_fragment(&marker.name));
+                        } else {
+                            out.push_str(&format!("<!-- smn-component: {} -->", e.to_string().replace("--", "-")));
+                        }
+                    }
+                }
+                rest = &rest[marker.end..];
+            }
+            out.push_str(rest);
+            assets.html = out;
+            Ok(assets)
+        })
+    }
+}
+
+/// What a slot comment records: enough to request the component again.
+#[derive(Serialize)]
+struct SlotInfo<'a> {
+    name: &'a str,
+    args: &'a [String],
+    props: &'a Map<String, Value>,
+}
+
+/// Opening slot comment. `--` may only occur inside JSON strings, where `\u002d` is equivalent,
+/// so the comment can never be closed early.
+fn slot_begin(name: &str, args: &[String], props: &ComponentProps) -> String {
+    let info = SlotInfo { name, args, props: props.as_map() };
+    let json = serde_json::to_string(&info).unwrap_or_else(|_| "{}".to_string());
+    format!("{SLOT_BEGIN}{}-->", json.replace("--", "-\\u002d"))
+}
+
+/// Insert `tags` right before `</head>` (or at the very start when there is no head).
+fn inject_head_tags(html: &str, tags: &str) -> String {
+    if tags.is_empty() {
+        return html.to_string();
+    }
+    match html.to_ascii_lowercase().find("</head>") {
+        Some(at) => format!("{}{tags}{}", &html[..at], &html[at..]),
+        None => format!("{tags}{html}"),
+    }
+}
+
+struct Marker {
+    /// Byte range of the whole marker (opening tag through closing tag).
+    start: usize,
+    end: usize,
+    name: String,
+    /// None when the attribute is absent (nested markers then inherit the parent's).
+    args: Option<Vec<String>>,
+    props: Option<ComponentProps>,
+}
+
+/// First well-formed marker in `html` that names a component.
+fn find_marker(html: &str) -> Option<Marker> {
+    let mut from = 0;
+    loop {
+        let start = from + html[from..].find(MARKER_OPEN)?;
+        let after_name = start + MARKER_OPEN.len();
+
+        // Must be the tag itself, not e.g. "<smn-component-list"
+        let next = html[after_name..].chars().next()?;
+        if !(next.is_whitespace() || next == '>' || next == '/') {
+            from = after_name;
+            continue;
+        }
+
+        let tag_end = after_name + find_tag_end(&html[after_name..])?;
+        let attrs_src = &html[after_name..tag_end];
+        let self_closing = attrs_src.trim_end().ends_with('/');
+        let end = if self_closing {
+            tag_end + 1
+        } else {
+            let close = html[tag_end..].find(MARKER_CLOSE)?;
+            tag_end + close + MARKER_CLOSE.len()
+        };
+
+        let attrs = parse_attrs(attrs_src.trim_end().trim_end_matches('/'));
+        let Some(name) = attr(&attrs, "name").filter(|n| !n.is_empty()) else {
+            from = end;
+            continue;
+        };
+
+        let args = attr(&attrs, "args").map(|a| {
+            serde_json::from_str::<Vec<Value>>(&a)
+                .map(|values| values.into_iter().map(value_to_arg).collect())
+                .unwrap_or_default()
+        });
+        let props = attr(&attrs, "props").map(|p| {
+            serde_json::from_str::<Map<String, Value>>(&p)
+                .map(ComponentProps::new)
+                .unwrap_or_default()
+        });
+
+        return Some(Marker { start, end, name, args, props });
+    }
+}
+
+/// Index of the `>` closing an opening tag, skipping quoted attribute values.
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `name="x" args='["a"]'` → [("name", "x"), ("args", "[\"a\"]")]; values are entity-decoded.
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let body = &after_eq[1..];
+                    let close = body.find(q).unwrap_or(body.len());
+                    value = decode_entities(&body[..close]);
+                    rest = body.get(close + 1..).unwrap_or("");
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    value = decode_entities(&after_eq[..end]);
+                    rest = &after_eq[end..];
+                }
+            }
+        }
+        if !key.is_empty() {
+            attrs.push((key, value));
+        }
+        rest = rest.trim_start();
+    }
+    attrs
+}
+
+fn attr(attrs: &[(String, String)], key: &str) -> Option<String> {
+    attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// `compArgs` are strings; stringify any other JSON scalars.
+fn value_to_arg(v: Value) -> String {
+    match v {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::plugin_components::{ComponentHandler, component_registry::ComponentSource};
+    use async_trait::async_trait;
+    use smn_web_core::systems::sys_plugin::PluginManager;
+
+    /// Renders a fixed snippet (which may contain markers).
+    struct Fixed {
+        name: &'static str,
+        html: String,
+    }
+
+    #[async_trait]
+    impl ComponentHandler for Fixed {
+        fn component_name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn component_parse(
+            &self,
+            _ctx: &ComponentContext,
+            _template: Option<String>,
+            _args: Vec<String>,
+            _props: ComponentProps,
+        ) -> Result<ComponentOutput, ComponentError> {
+            Ok(ComponentOutput::html(self.html.clone()))
+        }
+    }
+
+    fn marker(name: &str) -> String {
+        format!(r#"<smn-component name="{name}"></smn-component>"#)
+    }
+
+    fn includes(components: &[(&'static str, String)]) -> ComponentIncludes {
+        let mut registry = ComponentRegistry::default();
+        for (name, html) in components {
+            registry.insert(Arc::new(Fixed { name, html: html.clone() }), ComponentSource::Code);
+        }
+        ComponentIncludes::new(Arc::new(RwLock::new(registry)), false)
+    }
+
+    fn context() -> ComponentContext {
+        let req = Request::builder().uri("/index.html").body(Body::empty()).unwrap();
+        let plugin = PluginContext { broadcast: PluginManager::new().broadcast().clone() };
+        ComponentContext::from_request(&req, &plugin, false)
+    }
+
+    #[tokio::test]
+    async fn expands_nested_components() {
+        let inc = includes(&[("outer", format!("<b>{}</b>", marker("inner"))), ("inner", "hi".into())]);
+        let html = inc.expand(&context(), &format!("<p>{}</p>", marker("outer"))).await;
+        assert_eq!(html, "<p><b>hi</b></p>");
+    }
+
+    #[tokio::test]
+    async fn reports_include_cycles_with_their_chain() {
+        let inc = includes(&[("a", marker("b")), ("b", marker("a"))]);
+        let html = inc.expand(&context(), &marker("a")).await;
+        assert_eq!(html, "<!-- smn-component: component include cycle: a -> b -> a -->");
+
+        let nested = inc.expand_children(&context(), "a", &marker("b"), &[], &ComponentProps::default()).await;
+        match nested {
+            Err(IncludeError::Cycle(chain)) => assert_eq!(chain, ["a", "b", "a"]),
+            other => panic!("expected a cycle, got {:?}", other.map(|o| o.html)),
+        }
+    }
+
+    #[tokio::test]
+    async fn cuts_off_nesting_past_the_depth_limit() {
+        const NAMES: [&str; 12] = ["c0", "c1", "c2", "c3", "c4", "c5", "c6", "c7", "c8", "c9", "c10", "c11"];
+        let components: Vec<(&'static str, String)> = NAMES
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (*name, NAMES.get(i + 1).map(|next| marker(next)).unwrap_or_default()))
+            .collect();
+        let inc = includes(&components);
+
+        let html = inc.expand(&context(), &marker("c0")).await;
+        let chain = NAMES[..=MAX_INCLUDE_DEPTH].join(" -> ");
+        assert_eq!(
+            html,
+            format!("<!-- smn-component: component include depth limit ({MAX_INCLUDE_DEPTH}) exceeded: {chain} -->")
+        );
+
+        // Exactly at the limit still renders
+        let html = inc.expand(&context(), &marker(NAMES[NAMES.len() - MAX_INCLUDE_DEPTH])).await;
+        assert_eq!(html, "");
+    }
+
+    #[tokio::test]
+    async fn leaves_malformed_markers_intact() {
+        let inc = includes(&[("a", "rendered".into())]);
+        let ctx = context();
+        for page in [
+            r#"<smn-component name="a">"#,
+            r#"<smn-component name="a""#,
+            r#"<smn-component name="a>"#,
+            r#"<smn-component></smn-component>"#,
+            r#"<smn-component name=""></smn-component>"#,
+            r#"<smn-component-list name="a"></smn-component-list>"#,
+            "<smn-component",
+        ] {
+            assert_eq!(inc.expand(&ctx, page).await, page);
+        }
+        // A well-formed marker after a malformed one still expands
+        let page = format!(r#"<smn-component></smn-component>{}"#, marker("a"));
+        assert_eq!(inc.expand(&ctx, &page).await, "<smn-component></smn-component>rendered");
+    }
+
+    #[tokio::test]
+    async fn unknown_components_keep_their_marker() {
+        let inc = includes(&[]);
+        let page = format!("<p>{}</p>", marker("missing"));
+        assert_eq!(inc.expand(&context(), &page).await, page);
+    }
+}
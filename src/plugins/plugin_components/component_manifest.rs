@@ -0,0 +1,89 @@
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::plugins::plugin_components::component_describe::{ArgKind, ArgSpec, ComponentExample};
+
+/// `components/components.json`:
+/// { "components": [ { "name": "card", "template": "card.html",
+///                     "description": "Titled card",
+///                     "args": [ { "name": "title", "type": "string", "default": "Untitled" } ],
+///                     "example": { "compProps": { "title": "Hello" } } },
+///                   { "name": "socialLinks", "template": "socialLinks.html", "data": "components/socialLinks.json" } ] }
+#[derive(Debug, Deserialize)]
+pub struct ComponentManifest {
+    #[serde(default)]
+    pub components: Vec<ManifestEntry>,
+}
+
+/// One templated component: `{{arg}}` placeholders in `template` are replaced per request.
+/// With `data` the template is rendered by `template_engine` instead (`{{#each}}`, `{{#if}}`, …).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestEntry {
+    /// Route name: /components/<name>
+    pub name: String,
+    /// Template file, relative to the components directory
+    pub template: String,
+    /// Arguments in positional (`compArgs`) order; also addressable by name (`compProps`)
+    #[serde(default)]
+    pub args: Vec<ManifestArg>,
+    /// Shown by `/components/_index`
+    #[serde(default)]
+    pub description: String,
+    /// Representative arguments (defaults are used when absent)
+    #[serde(default)]
+    pub example: Option<ComponentExample>,
+    /// JSON data file bound to the template, relative to `./data`
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestArg {
+    pub name: String,
+    #[serde(default)]
+    pub default: String,
+    /// Expected value type; placeholders are always substituted as text
+    #[serde(default, rename = "type")]
+    pub kind: ArgKind,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl ManifestArg {
+    /// The argument as `describe()` reports it.
+    pub fn spec(&self) -> ArgSpec {
+        let spec = ArgSpec::new(&self.name, self.kind).describe(&self.description);
+        let spec = if self.required { spec.required() } else { spec };
+        if self.default.is_empty() { spec } else { spec.default_value(self.default.as_str()) }
+    }
+}
+
+/// Simple native error type
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self { Error::Io(e) }
+}
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self { Error::Json(e) }
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Json(e) => write!(f, "JSON parse error: {e}"),
+        }
+    }
+}
+impl std::error::Error for Error {}
+
+/// Load the manifest from a file path.
+pub fn load_manifest<P: AsRef<Path>>(path: P) -> Result<ComponentManifest, Error> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&text)?)
+}
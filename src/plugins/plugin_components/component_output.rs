@@ -0,0 +1,145 @@
+use hyper::{
+    Body, Response,
+    header::{CACHE_CONTROL, CONTENT_TYPE, HeaderValue, LINK},
+};
+use serde::Serialize;
+
+use crate::plugins::plugin_components::html_escape;
+
+const HTML: &str = "text/html; charset=utf-8";
+
+/// What a component successfully renders to (failures are `ComponentError`s). `PluginComponents` turns it into the HTTP response,
+/// a `_batch` entry or an inline include.
+/// - `stylesheets` / `scripts`: URLs the component needs in the page `<head>`
+///   (sent as `Link` headers, injected into `<head>` by server-side includes)
+/// - `cache`: how long the rendered HTML may be cached
+#[derive(Debug, Clone)]
+pub struct ComponentOutput {
+    pub content_type: &'static str,
+    pub html: String,
+    pub stylesheets: Vec<String>,
+    pub scripts: Vec<String>,
+    pub cache: CacheHint,
+}
+
+/// Caching policy for a component's output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheHint {
+    /// No `Cache-Control` header
+    #[default]
+    Unspecified,
+    /// Never cache (per-request content)
+    NoStore,
+    /// Cacheable for this many seconds
+    MaxAge(u32),
+}
+
+impl CacheHint {
+    /// The stricter of two hints, used when a component includes others.
+    pub fn combine(self, other: CacheHint) -> CacheHint {
+        match (self, other) {
+            (CacheHint::NoStore, _) | (_, CacheHint::NoStore) => CacheHint::NoStore,
+            (CacheHint::MaxAge(a), CacheHint::MaxAge(b)) => CacheHint::MaxAge(a.min(b)),
+            (CacheHint::MaxAge(a), _) | (_, CacheHint::MaxAge(a)) => CacheHint::MaxAge(a),
+            _ => CacheHint::Unspecified,
+        }
+    }
+
+    fn header_value(self) -> Option<String> {
+        match self {
+            CacheHint::Unspecified => None,
+            CacheHint::NoStore => Some("no-store".to_string()),
+            CacheHint::MaxAge(secs) => Some(format!("public, max-age={secs}")),
+        }
+    }
+}
+
+impl ComponentOutput {
+    /// 200 text/html.
+    pub fn html(html: impl Into<String>) -> Self {
+        Self {
+            content_type: HTML,
+            html: html.into(),
+            stylesheets: Vec::new(),
+            scripts: Vec::new(),
+            cache: CacheHint::Unspecified,
+        }
+    }
+
+    pub fn with_stylesheet(mut self, url: impl Into<String>) -> Self {
+        push_unique(&mut self.stylesheets, url.into());
+        self
+    }
+
+    pub fn with_script(mut self, url: impl Into<String>) -> Self {
+        push_unique(&mut self.scripts, url.into());
+        self
+    }
+
+    pub fn with_cache(mut self, cache: CacheHint) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn is_html(&self) -> bool {
+        self.content_type.starts_with("text/html")
+    }
+
+    /// Take over the assets and cache policy of an included component.
+    pub fn absorb_assets(&mut self, child: &ComponentOutput) {
+        for url in &child.stylesheets {
+            push_unique(&mut self.stylesheets, url.clone());
+        }
+        for url in &child.scripts {
+            push_unique(&mut self.scripts, url.clone());
+        }
+        self.cache = self.cache.combine(child.cache);
+    }
+
+    /// `<link>` / `<script>` tags for the assets `page` doesn't reference yet.
+    pub fn head_tags(&self, page: &str) -> String {
+        let mut tags = String::new();
+        for url in &self.stylesheets {
+            let href = html_escape(url);
+            if !page.contains(&format!("href=\"{href}\"")) {
+                tags.push_str(&format!("<link rel=\"stylesheet\" href=\"{href}\" />\n"));
+            }
+        }
+        for url in &self.scripts {
+            let src = html_escape(url);
+            if !page.contains(&format!("src=\"{src}\"")) {
+                tags.push_str(&format!("<script src=\"{src}\" defer></script>\n"));
+            }
+        }
+        tags
+    }
+
+    /// HTTP response: body, Content-Type, Cache-Control and one `Link` header per asset
+    /// (`rel="stylesheet"`, or `rel="preload"; as="script"`), read by `componentManager.js`.
+    pub fn into_response(self) -> Response<Body> {
+        let mut resp = Response::new(Body::from(self.html));
+        let headers = resp.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
+        if let Some(cc) = self.cache.header_value().and_then(|v| HeaderValue::from_str(&v).ok()) {
+            headers.insert(CACHE_CONTROL, cc);
+        }
+        let links = self
+            .stylesheets
+            .iter()
+            .map(|u| format!("<{u}>; rel=\"stylesheet\""))
+            .chain(self.scripts.iter().map(|u| format!("<{u}>; rel=\"preload\"; as=\"script\"")));
+        for link in links {
+            if let Ok(v) = HeaderValue::from_str(&link) {
+                headers.append(LINK, v);
+            }
+        }
+        resp
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, url: String) {
+    if !list.contains(&url) {
+        list.push(url);
+    }
+}
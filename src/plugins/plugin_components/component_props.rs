@@ -0,0 +1,174 @@
+use serde_json::{Map, Value};
+
+/// Named component arguments.
+/// - POST: `{ "compProps": { "heading": "Hi", "count": 3, "tags": ["a", "b"] } }`
+/// - GET:  `?prop.heading=Hi&prop.tags=a&prop.tags=b&prop.link.href=/x`
+///   (repeated names become arrays, dots build nested objects, values stay strings)
+#[derive(Debug, Clone, Default)]
+pub struct ComponentProps {
+    map: Map<String, Value>,
+}
+
+impl ComponentProps {
+    pub fn new(map: Map<String, Value>) -> Self {
+        Self { map }
+    }
+
+    /// Raw JSON value for `name`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.map.get(name)
+    }
+
+    /// String value; numbers and bools are stringified so query and JSON callers behave the same.
+    pub fn get_str(&self, name: &str) -> Option<String> {
+        match self.map.get(name)? {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Numeric value; accepts numeric strings (query parameters are always strings).
+    pub fn get_f64(&self, name: &str) -> Option<f64> {
+        match self.map.get(name)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Boolean value; accepts "true"/"false"/"1"/"0" strings.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.map.get(name)? {
+            Value::Bool(b) => Some(*b),
+            Value::String(s) => match s.trim() {
+                "true" | "1" => Some(true),
+                "false" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Array value; a single scalar is treated as a one-element array.
+    pub fn get_array(&self, name: &str) -> Vec<Value> {
+        match self.map.get(name) {
+            Some(Value::Array(a)) => a.clone(),
+            Some(Value::Null) | None => Vec::new(),
+            Some(v) => vec![v.clone()],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn as_map(&self) -> &Map<String, Value> {
+        &self.map
+    }
+
+    /// Parse every `prop.<path>=<value>` pair of a query string.
+    pub fn from_query(qs: &str) -> Self {
+        let mut map = Map::new();
+        for pair in qs.split('&') {
+            let mut it = pair.splitn(2, '=');
+            let Some(key) = it.next().and_then(decode_query_part) else {
+                continue;
+            };
+            let Some(path) = key.strip_prefix("prop.") else {
+                continue;
+            };
+            let value = it.next().and_then(decode_query_part).unwrap_or_default();
+            let segments: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
+            if !segments.is_empty() {
+                insert_path(&mut map, &segments, Value::String(value));
+            }
+        }
+        Self { map }
+    }
+}
+
+/// `application/x-www-form-urlencoded` decoding: `+` is a space, then `%XX` escapes.
+fn decode_query_part(s: &str) -> Option<String> {
+    urlencoding::decode(&s.replace('+', " ")).ok().map(|s| s.into_owned())
+}
+
+/// Insert `value` at a dotted path, turning repeats into arrays.
+fn insert_path(map: &mut Map<String, Value>, path: &[&str], value: Value) {
+    let (head, rest) = (path[0], &path[1..]);
+    if rest.is_empty() {
+        match map.get_mut(head) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => {
+                let prev = existing.take();
+                *existing = Value::Array(vec![prev, value]);
+            }
+            None => {
+                map.insert(head.to_string(), value);
+            }
+        }
+        return;
+    }
+
+    let entry = map
+        .entry(head.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    if let Value::Object(child) = entry {
+        insert_path(child, rest, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(qs: &str) -> Value {
+        Value::Object(ComponentProps::from_query(qs).map)
+    }
+
+    #[test]
+    fn from_query_builds_nested_props() {
+        assert_eq!(
+            query("prop.heading=Hi&other=1&prop.link.href=/x&prop.link.label=Go"),
+            json!({ "heading": "Hi", "link": { "href": "/x", "label": "Go" } })
+        );
+        assert_eq!(query(""), json!({}));
+        assert_eq!(query("prop.=x&prop..=y&prop"), json!({}));
+    }
+
+    #[test]
+    fn from_query_empty_and_repeated_values() {
+        assert_eq!(query("prop.a=&prop.b"), json!({ "a": "", "b": "" }));
+        assert_eq!(query("prop.tags=a&prop.tags=b&prop.tags=c"), json!({ "tags": ["a", "b", "c"] }));
+    }
+
+    #[test]
+    fn from_query_splits_on_first_equals_only() {
+        assert_eq!(query("prop.q=a:b=c&prop.t=12:30"), json!({ "q": "a:b=c", "t": "12:30" }));
+    }
+
+    #[test]
+    fn from_query_decodes_keys_and_values_alike() {
+        assert_eq!(
+            query("prop.my+title=Hello+World%21&prop.caf%C3%A9=a%2Bb%26c%3Dd&prop%2Elink.x=%2F"),
+            json!({ "my title": "Hello World!", "café": "a+b&c=d", "link": { "x": "/" } })
+        );
+        // Invalid UTF-8 drops the key, or leaves an empty value
+        assert_eq!(query("prop.%FF=1&prop.v=%FF"), json!({ "v": "" }));
+    }
+
+    #[test]
+    fn typed_getters_accept_query_strings() {
+        let props = ComponentProps::from_query("prop.n=2.5&prop.on=1&prop.off=false&prop.one=x");
+        assert_eq!(props.get_f64("n"), Some(2.5));
+        assert_eq!(props.get_bool("on"), Some(true));
+        assert_eq!(props.get_bool("off"), Some(false));
+        assert_eq!(props.get_array("one"), vec![json!("x")]);
+        assert!(props.get_array("missing").is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, RwLock},
+    time::SystemTime,
+};
+
+use crate::plugins::plugin_components::{ComponentDescription, ComponentHandler, component_style};
+
+/// Where a registered component came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentSource {
+    /// `register` / `register_simple` from Rust
+    Code,
+    /// `components/components.json`
+    Manifest,
+    /// Found by scanning the components directory (templates and WebAssembly modules)
+    Discovered,
+}
+
+impl fmt::Display for ComponentSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentSource::Code => write!(f, "code"),
+            ComponentSource::Manifest => write!(f, "manifest"),
+            ComponentSource::Discovered => write!(f, "discovered"),
+        }
+    }
+}
+
+struct Entry {
+    handler: Arc<dyn ComponentHandler>,
+    source: ComponentSource,
+    /// `handler.describe()`, taken once at insert.
+    description: Option<Arc<ComponentDescription>>,
+    /// File a discovered component was built from.
+    discovered: Option<PathBuf>,
+    /// Preloaded template, kept fresh by `refresh_templates`.
+    template: Option<CachedTemplate>,
+    /// `<name>/style.css`, already scoped to the component's root element.
+    style: Option<CachedTemplate>,
+}
+
+/// A template read from disk, with the modification time it was read at.
+#[derive(Clone)]
+struct CachedTemplate {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    contents: String,
+}
+
+/// Name → handler map shared between the plugin and its discovery task.
+#[derive(Default)]
+pub struct ComponentRegistry {
+    entries: HashMap<&'static str, Entry>,
+}
+
+impl ComponentRegistry {
+    pub fn insert(&mut self, handler: Arc<dyn ComponentHandler>, source: ComponentSource) {
+        let description = handler.describe().map(Arc::new);
+        self.entries.insert(
+            handler.component_name(),
+            Entry { handler, source, description, discovered: None, template: None, style: None },
+        );
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ComponentHandler>> {
+        self.entries.get(name).map(|e| e.handler.clone())
+    }
+
+    /// Handler plus its cached template (no disk access).
+    pub fn get_with_template(&self, name: &str) -> Option<(Arc<dyn ComponentHandler>, Option<String>)> {
+        self.entries
+            .get(name)
+            .map(|e| (e.handler.clone(), e.template.as_ref().map(|t| t.contents.clone())))
+    }
+
+    /// What the handler's `describe()` returned when it was registered.
+    pub fn description(&self, name: &str) -> Option<Arc<ComponentDescription>> {
+        self.entries.get(name)?.description.clone()
+    }
+
+    /// Scoped stylesheet of a folder component.
+    pub fn style(&self, name: &str) -> Option<String> {
+        self.entries.get(name)?.style.as_ref().map(|s| s.contents.clone())
+    }
+
+    /// Changes whenever the component's `style.css` does (for cache-busting URLs).
+    pub fn style_version(&self, name: &str) -> Option<u64> {
+        let style = self.entries.get(name)?.style.as_ref()?;
+        Some(
+            style
+                .modified
+                .and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs()),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// (name, source) pairs sorted by name.
+    pub fn list(&self) -> Vec<(&'static str, ComponentSource)> {
+        let mut items: Vec<_> = self.entries.iter().map(|(n, e)| (*n, e.source)).collect();
+        items.sort_by_key(|(n, _)| *n);
+        items
+    }
+
+    /// Bring discovered components in line with what is on disk: register new files
+    /// (never shadowing code or manifest components) and drop discovered ones whose file is gone
+    /// or changed kind (e.g. a template replaced by a `.wasm` module).
+    /// `make` builds the handler for a newly found file.
+    /// Returns the (added, removed) names.
+    pub fn sync_discovered<F>(&mut self, found: &[(String, PathBuf)], make: F) -> (Vec<String>, Vec<String>)
+    where
+        F: Fn(&'static str, PathBuf) -> Arc<dyn ComponentHandler>,
+    {
+        let removed: Vec<&'static str> = self
+            .entries
+            .iter()
+            .filter(|(name, e)| {
+                e.source == ComponentSource::Discovered
+                    && !found.iter().any(|(n, p)| n == *name && e.discovered.as_ref() == Some(p))
+            })
+            .map(|(name, _)| *name)
+            .collect();
+        for name in &removed {
+            self.entries.remove(name);
+        }
+
+        let mut added = Vec::new();
+        for (name, path) in found {
+            if self.entries.contains_key(name.as_str()) {
+                continue;
+            }
+            let name_static = intern_name(name);
+            self.insert(make(name_static, path.clone()), ComponentSource::Discovered);
+            if let Some(entry) = self.entries.get_mut(name_static) {
+                entry.discovered = Some(path.clone());
+            }
+            added.push(name.clone());
+        }
+
+        // A replaced component shows up in both lists; report it as added only
+        let removed = removed
+            .into_iter()
+            .filter(|r| !added.iter().any(|a| a == r))
+            .map(str::to_string)
+            .collect();
+        (added, removed)
+    }
+}
+
+/// `&'static str` for a component name. Each distinct name is leaked once and reused, so
+/// components that come and go while serving don't leak a new string every time.
+pub fn intern_name(name: &str) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut names = NAMES.get_or_init(Default::default).lock().expect("component name table poisoned");
+    if let Some(interned) = names.get(name) {
+        return interned;
+    }
+    let leaked: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(leaked);
+    leaked
+}
+
+/// Template candidates for a component, in loader order:
+/// `<dir>/<name>/template.html`, `<dir>/<name>.html`, then the handler's own template file.
+fn template_candidates(dir: &Path, name: &str, handler: &dyn ComponentHandler) -> Vec<PathBuf> {
+    let mut candidates = vec![
+        dir.join(name).join("template.html"),
+        dir.join(format!("{name}.html")),
+    ];
+    if let Some(own) = handler.template_path() {
+        candidates.push(own.to_path_buf());
+    }
+    candidates
+}
+
+/// Path and modification time a cached template was read from.
+type FileStamp = (PathBuf, Option<SystemTime>);
+
+/// Which cached file of an entry an update is for.
+#[derive(Clone, Copy)]
+enum CachedFile {
+    Template,
+    Style,
+}
+
+/// Load or revalidate every component's template (and folder `style.css`) by path and
+/// modification time: unchanged files are not re-read, changed ones are reloaded, vanished ones
+/// dropped. File I/O happens outside the lock. Returns the names whose template or style changed.
+pub fn refresh_templates(registry: &RwLock<ComponentRegistry>, dir: &Path) -> Vec<String> {
+    let snapshot: Vec<(&'static str, CachedFile, Vec<PathBuf>, Option<FileStamp>)> = registry
+        .read()
+        .expect("component registry lock poisoned")
+        .entries
+        .iter()
+        .flat_map(|(name, e)| {
+            let stamp = |c: &Option<CachedTemplate>| c.as_ref().map(|t| (t.path.clone(), t.modified));
+            [
+                (*name, CachedFile::Template, template_candidates(dir, name, e.handler.as_ref()), stamp(&e.template)),
+                (*name, CachedFile::Style, vec![dir.join(name).join("style.css")], stamp(&e.style)),
+            ]
+        })
+        .collect();
+
+    let mut updates: Vec<(&'static str, CachedFile, Option<CachedTemplate>)> = Vec::new();
+    for (name, kind, candidates, cached) in snapshot {
+        let current = candidates.into_iter().find_map(|p| {
+            let md = std::fs::metadata(&p).ok().filter(|m| m.is_file())?;
+            Some((p, md.modified().ok()))
+        });
+
+        match (current, cached) {
+            (Some((path, modified)), Some((cached_path, cached_modified)))
+                if path == cached_path && modified == cached_modified => {}
+            (Some((path, modified)), _) => match std::fs::read(&path) {
+                Ok(bytes) => {
+                    let raw = String::from_utf8_lossy(&bytes);
+                    let contents = match kind {
+                        CachedFile::Template => raw.into_owned(),
+                        CachedFile::Style => component_style::scope_css(&raw, name),
+                    };
+                    updates.push((name, kind, Some(CachedTemplate { path, modified, contents })));
+                }
+                Err(e) => eprintln!("WARN: Failed to read template '{}': {e}", path.display()),
+            },
+            (None, Some(_)) => updates.push((name, kind, None)),
+            (None, None) => {}
+        }
+    }
+
+    let mut reg = registry.write().expect("component registry lock poisoned");
+    let mut changed = Vec::new();
+    for (name, kind, cached) in updates {
+        // The component may have been removed while we were reading.
+        if let Some(entry) = reg.entries.get_mut(name) {
+            match kind {
+                CachedFile::Template => entry.template = cached,
+                CachedFile::Style => entry.style = cached,
+            }
+            if !changed.iter().any(|c| c == name) {
+                changed.push(name.to_string());
+            }
+        }
+    }
+    changed
+}
+
+/// Scan `dir` for components: WebAssembly modules (`<name>.wasm`, `<name>/component.wasm`) and
+/// templates (`<name>.html`, `<name>/template.html`). A module wins over a template of the same
+/// name (it renders that template); hidden entries are skipped; when both forms exist the folder
+/// wins (as in the request loader).
+pub fn discover_components(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut found: Vec<(String, PathBuf)> = Vec::new();
+    for entry in read.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            let candidate = [path.join("component.wasm"), path.join("template.html")]
+                .into_iter()
+                .find(|p| p.is_file());
+            if let Some(candidate) = candidate {
+                add_found(&mut found, file_name, candidate);
+            }
+        } else if matches!(path.extension().and_then(|e| e.to_str()), Some("html" | "wasm")) {
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            add_found(&mut found, stem, path.clone());
+        }
+    }
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
+
+/// Whether `path` is a WebAssembly component module.
+pub fn is_wasm(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("wasm")
+}
+
+/// Record `path` for `name` unless a higher-ranked file was already found:
+/// modules over templates, then folder over flat files.
+fn add_found(found: &mut Vec<(String, PathBuf)>, name: &str, path: PathBuf) {
+    let rank = |p: &Path| {
+        let in_folder = p.parent().and_then(|d| d.file_name()).and_then(|f| f.to_str()) == Some(name);
+        (is_wasm(p), in_folder)
+    };
+    match found.iter_mut().find(|(n, _)| n == name) {
+        Some(existing) if rank(&path) > rank(&existing.1) => existing.1 = path,
+        Some(_) => {}
+        None => found.push((name.to_string(), path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::plugin_components::{ComponentContext, ComponentError, ComponentOutput, ComponentProps};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts how often it is asked to describe itself.
+    #[derive(Default)]
+    struct Counting {
+        describes: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ComponentHandler for Counting {
+        fn component_name(&self) -> &'static str {
+            "countingTestComponent"
+        }
+
+        fn describe(&self) -> Option<ComponentDescription> {
+            self.describes.fetch_add(1, Ordering::SeqCst);
+            Some(ComponentDescription { description: "counted".to_string(), ..Default::default() })
+        }
+
+        async fn component_parse(
+            &self,
+            _ctx: &ComponentContext,
+            _template: Option<String>,
+            _args: Vec<String>,
+            _props: ComponentProps,
+        ) -> Result<ComponentOutput, ComponentError> {
+            Ok(ComponentOutput::html(""))
+        }
+    }
+
+    #[test]
+    fn description_is_taken_once_at_insert() {
+        let handler = Arc::new(Counting::default());
+        let mut registry = ComponentRegistry::default();
+        registry.insert(handler.clone(), ComponentSource::Code);
+        for _ in 0..3 {
+            let description = registry.description("countingTestComponent").expect("described");
+            assert_eq!(description.description, "counted");
+        }
+        assert_eq!(handler.describes.load(Ordering::SeqCst), 1);
+        assert!(registry.description("missing").is_none());
+    }
+
+    #[test]
+    fn interned_names_are_reused() {
+        let first = intern_name("internTestComponent");
+        let again = intern_name(&String::from("internTestComponent"));
+        assert!(std::ptr::eq(first, again));
+        assert_ne!(intern_name("internTestOther"), first);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::plugins::plugin_components::html_escape;
+
+/// Attribute marking a component's root element; scoped styles select on it.
+pub const SCOPE_ATTR: &str = "data-smn-component";
+
+/// Selector matching the root element of component `name`.
+pub fn scope_selector(name: &str) -> String {
+    let quoted = name.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{SCOPE_ATTR}=\"{quoted}\"]")
+}
+
+/// Scope a component's `style.css` to its root element:
+/// every selector gets the root as ancestor (`.title` → `[data-smn-component="card"] .title`),
+/// `:scope` / `:host` / `:root` stand for the root itself. Rules inside `@media` / `@supports` / `@container`
+/// / `@layer` blocks are scoped too; other at-rules (`@keyframes`, `@font-face`, ...) are kept as is.
+pub fn scope_css(css: &str, name: &str) -> String {
+    let scope = scope_selector(name);
+    let mut out = String::with_capacity(css.len() + css.len() / 4);
+    scope_block(&strip_comments(css), &scope, &mut out);
+    out
+}
+
+/// Add the scope attribute to the first element of `html` (the component's root).
+/// Leaves the markup untouched if it already carries one or has no element.
+pub fn scope_root(html: &str, name: &str) -> String {
+    let mut from = 0;
+    while let Some(rel) = html[from..].find('<') {
+        let start = from + rel;
+        let rest = &html[start + 1..];
+        if rest.starts_with("!--") {
+            from = start + rest.find("-->").map_or(html.len() - start, |e| e + 4);
+            continue;
+        }
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            from = start + 1;
+            continue;
+        }
+        let name_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(rest.len());
+        let Some(tag_end) = rest.find('>') else {
+            return html.to_string();
+        };
+        if rest[..tag_end].contains(SCOPE_ATTR) {
+            return html.to_string();
+        }
+        let at = start + 1 + name_len;
+        return format!("{} {SCOPE_ATTR}=\"{}\"{}", &html[..at], html_escape(name), &html[at..]);
+    }
+    html.to_string()
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut i = 0;
+    while i < css.len() {
+        match literal_end(css, i) {
+            Some(end) => {
+                // Strings are kept, comments dropped
+                if !css[i..].starts_with("/*") {
+                    out.push_str(&css[i..end]);
+                }
+                i = end;
+            }
+            None => {
+                let c = css[i..].chars().next().unwrap_or_default();
+                out.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    out
+}
+
+/// If a string (`"..."`, `'...'`) or comment (`/* ... */`) starts at byte `i`, the index just
+/// past it (the end of `s` when unterminated).
+fn literal_end(s: &str, i: usize) -> Option<usize> {
+    let rest = &s[i..];
+    if let Some(body) = rest.strip_prefix("/*") {
+        return Some(body.find("*/").map_or(s.len(), |e| i + 2 + e + 2));
+    }
+    let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let mut escaped = false;
+    for (j, c) in rest.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return Some(i + j + 1),
+            _ => {}
+        }
+    }
+    Some(s.len())
+}
+
+/// First of `chars` at or after byte `from` that is outside strings and comments.
+fn find_outside_literals(s: &str, from: usize, chars: &[char]) -> Option<usize> {
+    let mut i = from;
+    while i < s.len() {
+        if let Some(end) = literal_end(s, i) {
+            i = end;
+            continue;
+        }
+        let c = s[i..].chars().next()?;
+        if chars.contains(&c) {
+            return Some(i);
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+/// Scope every rule of a block body (the stylesheet itself or a grouping at-rule).
+fn scope_block(css: &str, scope: &str, out: &mut String) {
+    let mut rest = css;
+    loop {
+        let trimmed = rest.trim_start();
+        if trimmed.is_empty() {
+            return;
+        }
+        let Some(open) = find_outside_literals(trimmed, 0, &['{', ';']) else {
+            out.push_str(trimmed);
+            return;
+        };
+
+        let prelude = trimmed[..open].trim();
+        // Statement at-rules: @import, @charset, @layer a, b;
+        if trimmed.as_bytes()[open] == b';' {
+            out.push_str(prelude);
+            out.push_str(";\n");
+            rest = &trimmed[open + 1..];
+            continue;
+        }
+
+        let Some(close) = matching_brace(trimmed, open) else {
+            out.push_str(trimmed);
+            return;
+        };
+        let body = &trimmed[open + 1..close];
+
+        if let Some(at_rule) = prelude.strip_prefix('@') {
+            let keyword = at_rule.split(|c: char| c.is_whitespace() || c == '(').next().unwrap_or("");
+            out.push_str(prelude);
+            out.push_str(" {\n");
+            if matches!(keyword, "media" | "supports" | "container" | "layer" | "document") {
+                scope_block(body, scope, out);
+            } else {
+                out.push_str(body.trim());
+                out.push('\n');
+            }
+            out.push_str("}\n");
+        } else {
+            out.push_str(&scope_selectors(prelude, scope));
+            out.push_str(" {");
+            out.push_str(body);
+            out.push_str("}\n");
+        }
+        rest = &trimmed[close + 1..];
+    }
+}
+
+/// Index of the `}` closing the `{` at `open`; braces in strings and comments don't count.
+fn matching_brace(s: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = open;
+    while let Some(at) = find_outside_literals(s, i, &['{', '}']) {
+        if s.as_bytes()[at] == b'{' {
+            depth += 1;
+        } else {
+            depth -= 1;
+            if depth == 0 {
+                return Some(at);
+            }
+        }
+        i = at + 1;
+    }
+    None
+}
+
+fn scope_selectors(list: &str, scope: &str) -> String {
+    split_top_level_commas(list)
+        .into_iter()
+        .map(|sel| {
+            let sel = sel.trim();
+            for root in [":scope", ":host", ":root"] {
+                if let Some(rest) = sel.strip_prefix(root) {
+                    return format!("{scope}{rest}");
+                }
+            }
+            format!("{scope} {sel}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Split a selector list on commas outside of parentheses (`:is(a, b)`) and brackets.
+fn split_top_level_commas(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&list[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S: &str = r#"[data-smn-component="card"]"#;
+
+    fn scoped(css: &str) -> String {
+        scope_css(css, "card")
+    }
+
+    #[test]
+    fn selector_lists_and_root_aliases() {
+        assert_eq!(scoped(".a, h2 > b{color:red}"), format!("{S} .a, {S} h2 > b {{color:red}}\n"));
+        assert_eq!(scoped(":is(.a, .b) p {x:y}"), format!("{S} :is(.a, .b) p {{x:y}}\n"));
+        assert_eq!(
+            scoped(":root{--c:1} :scope.big, :host > p {x:y}"),
+            format!("{S} {{--c:1}}\n{S}.big, {S} > p {{x:y}}\n")
+        );
+    }
+
+    #[test]
+    fn media_rules_are_scoped_inside() {
+        assert_eq!(
+            scoped("@media (max-width: 600px) { .a { x:y } .b, .c { z:w } }"),
+            format!("@media (max-width: 600px) {{\n{S} .a {{ x:y }}\n{S} .b, {S} .c {{ z:w }}\n}}\n")
+        );
+    }
+
+    #[test]
+    fn keyframes_and_statements_are_kept() {
+        let css = "@import url(\"x.css\");\n@keyframes spin { from { a:b } to { a:c } }\n.a{x:y}";
+        assert_eq!(
+            scoped(css),
+            format!("@import url(\"x.css\");\n@keyframes spin {{\nfrom {{ a:b }} to {{ a:c }}\n}}\n{S} .a {{x:y}}\n")
+        );
+    }
+
+    #[test]
+    fn braces_in_strings_and_comments_are_ignored() {
+        assert_eq!(
+            scoped(r#".a::before { content: "}{"; } /* } { */ .b[title='{'] { x: '\'}' }"#),
+            format!("{S} .a::before {{ content: \"}}{{\"; }}\n{S} .b[title='{{'] {{ x: '\\'}}' }}\n")
+        );
+        assert_eq!(scoped(r#".a { content: "/* kept */" }"#), format!("{S} .a {{ content: \"/* kept */\" }}\n"));
+        assert_eq!(matching_brace("{ a: \"}\" /* } */ }", 0), Some(17));
+        assert_eq!(matching_brace("{ \"unterminated }", 0), None);
+    }
+
+    #[test]
+    fn scope_root_marks_first_element_escaped() {
+        assert_eq!(
+            scope_root("<!-- c --> text <div class=\"x\"><p></p></div>", "card"),
+            r#"<!-- c --> text <div data-smn-component="card" class="x"><p></p></div>"#
+        );
+        assert_eq!(scope_root("plain text", "card"), "plain text");
+        let marked = r#"<div data-smn-component="other"></div>"#;
+        assert_eq!(scope_root(marked, "card"), marked);
+        assert_eq!(
+            scope_root("<b>x</b>", r#"a"><script>"#),
+            r#"<b data-smn-component="a&quot;&gt;&lt;script&gt;">x</b>"#
+        );
+        assert_eq!(scope_selector(r#"a"b\"#), r#"[data-smn-component="a\"b\\"]"#);
+    }
+}
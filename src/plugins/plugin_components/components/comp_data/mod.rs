@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentDescription, ComponentError, ComponentHandler, ComponentOutput, ComponentProps,
+    component_describe::{ArgKind, ComponentExample},
+    component_manifest::ManifestArg,
+    template_engine,
+};
+
+/// A manifest component bound to a JSON file under `./data`, rendered with `template_engine`
+/// (`{{#each}}`, `{{#if}}`, escaped `{{value}}`). The data file is read on every request and the
+/// template comes from the registry cache, so edits to either show up without a restart.
+/// Declared args are available by name and override data keys of the same name.
+pub struct DataComponent {
+    name: &'static str,
+    path: PathBuf,
+    data_path: PathBuf,
+    args: Vec<ManifestArg>,
+    description: String,
+    example: Option<ComponentExample>,
+}
+
+impl DataComponent {
+    pub fn new(name_static: &'static str, path: PathBuf, data_path: PathBuf, args: Vec<ManifestArg>) -> Self {
+        Self {
+            name: name_static,
+            path,
+            data_path,
+            args,
+            description: String::new(),
+            example: None,
+        }
+    }
+
+    /// Manifest `description` / `example` for `describe()`.
+    pub fn with_description(mut self, description: String, example: Option<ComponentExample>) -> Self {
+        self.description = description;
+        self.example = example;
+        self
+    }
+
+    /// Named values for the template: `compProps`, then the positional `compArgs` slot,
+    /// then the manifest default. Text values of bool / number args are converted, so that
+    /// `?prop.flag=false` is falsy in `{{#if flag}}`.
+    fn bound_args(&self, args: &[String], props: &ComponentProps) -> Map<String, Value> {
+        let mut bound = Map::new();
+        for (i, arg) in self.args.iter().enumerate() {
+            let value = match props.get(&arg.name) {
+                Some(v) => v.clone(),
+                None => match args.get(i) {
+                    Some(v) => Value::String(v.clone()),
+                    None if arg.default.is_empty() => continue,
+                    None => Value::String(arg.default.clone()),
+                },
+            };
+            bound.insert(arg.name.clone(), coerce(value, arg.kind));
+        }
+        bound
+    }
+
+    async fn load_data(&self) -> Result<Value, ComponentError> {
+        let text = match tokio::fs::read_to_string(&self.data_path).await {
+            Ok(t) => t,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ComponentError::Render(format!(
+                    "data file '{}' not found",
+                    self.data_path.display()
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&text).map_err(|e| {
+            ComponentError::Render(format!("data file '{}': {e}", self.data_path.display()))
+        })
+    }
+}
+
+/// Convert a text value to the declared kind; anything unparseable is left as is.
+fn coerce(value: Value, kind: ArgKind) -> Value {
+    let Value::String(text) = &value else {
+        return value;
+    };
+    let parsed = match kind {
+        ArgKind::Bool => match text.trim() {
+            "true" | "1" => Some(Value::Bool(true)),
+            "false" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ArgKind::Number | ArgKind::Array | ArgKind::Object => {
+            serde_json::from_str::<Value>(text.trim()).ok().filter(|v| match kind {
+                ArgKind::Number => v.is_number(),
+                ArgKind::Array => v.is_array(),
+                _ => v.is_object(),
+            })
+        }
+        ArgKind::String => None,
+    };
+    parsed.unwrap_or(value)
+}
+
+#[async_trait]
+impl ComponentHandler for DataComponent {
+    fn component_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn template_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn describe(&self) -> Option<ComponentDescription> {
+        let description = if self.description.is_empty() {
+            format!("Template {} bound to {}", self.path.display(), self.data_path.display())
+        } else {
+            self.description.clone()
+        };
+        Some(ComponentDescription {
+            description,
+            args: self.args.iter().map(ManifestArg::spec).collect(),
+            example: Some(self.example.clone().unwrap_or_default()),
+        })
+    }
+
+    async fn component_parse(
+        &self,
+        _ctx: &ComponentContext,
+        template: Option<String>,
+        args: Vec<String>,
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        let tpl = match template {
+            Some(t) => t,
+            None => match tokio::fs::read_to_string(&self.path).await {
+                Ok(t) => t,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(ComponentError::TemplateMissing(self.path.display().to_string()));
+                }
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        let data = self.load_data().await?;
+        let root = template_engine::root_value(&data, &self.bound_args(&args, &props));
+        let html = template_engine::render(&tpl, &root)
+            .map_err(|e| ComponentError::Render(format!("template '{}': {e}", self.path.display())))?;
+        Ok(ComponentOutput::html(html))
+    }
+}
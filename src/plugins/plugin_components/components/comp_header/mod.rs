@@ -1,69 +1,59 @@
 use async_trait::async_trait;
-use hyper::{
-    Body, Response, StatusCode,
-    header::{CONTENT_TYPE, HeaderValue},
-};
-use std::convert::Infallible;
 
-use crate::plugins::plugin_components::{ComponentHandler, respond_status};
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentDescription, ComponentError, ComponentHandler, ComponentOutput, ComponentProps,
+    component_describe::{ArgKind, ArgSpec, ComponentExample},
+    html_escape,
+};
 
 /// Header component
-/// Args: [section_heading]
+/// Props: { section_heading }
+/// Args (legacy): [section_heading]
 pub struct CompHeader;
 
+const DEFAULT_HEADING: &str = "Technical Art";
+
 #[async_trait]
 impl ComponentHandler for CompHeader {
     fn component_name(&self) -> &'static str {
         "header"
     }
 
+    fn describe(&self) -> Option<ComponentDescription> {
+        Some(ComponentDescription {
+            description: "Page header with a section heading".to_string(),
+            args: vec![
+                ArgSpec::new("section_heading", ArgKind::String)
+                    .default_value(DEFAULT_HEADING)
+                    .describe("Heading text shown in the header"),
+            ],
+            example: Some(ComponentExample {
+                compArgs: vec!["Game Design".to_string()],
+                ..Default::default()
+            }),
+        })
+    }
+
     async fn component_parse(
         &self,
+        _ctx: &ComponentContext,
         template: Option<String>,
         args: Vec<String>,
-    ) -> Result<Response<Body>, Infallible> {
-        // Desired heading text, default if missing
-        let section_heading = args.get(0)
-            .map(|s| s.as_str())
-            .unwrap_or("Technical Art");
-
-        let tpl = match template {
-            Some(t) => t,
-            None => {
-                let mut r = respond_status(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Header template not found: expected components/header.html (or components/header/template.html)"
-                );
-                r.headers_mut().insert(
-                    CONTENT_TYPE,
-                    HeaderValue::from_static("text/plain; charset=utf-8"),
-                );
-                return Ok(r);
-            }
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        // Desired heading text: named prop first, then the legacy positional arg, then a default
+        let section_heading = props.get_str("section_heading")
+            .or_else(|| args.first().cloned())
+            .unwrap_or_else(|| DEFAULT_HEADING.to_string());
+
+        let Some(tpl) = template else {
+            return Err(ComponentError::TemplateMissing(
+                "components/header.html (or components/header/template.html)".to_string(),
+            ));
         };
 
         // Replace all {{section_heading}} placeholders
-        let html = tpl.replace("{{section_heading}}", &html_escape(section_heading));
-
-        let mut resp = Response::new(Body::from(html));
-        resp.headers_mut().insert(
-            CONTENT_TYPE,
-            HeaderValue::from_static("text/html; charset=utf-8"),
-        );
-        Ok(resp)
+        let html = tpl.replace("{{section_heading}}", &html_escape(&section_heading));
+        Ok(ComponentOutput::html(html))
     }
 }
-
-/// Simple HTML escaper
-fn html_escape(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '&' => "&amp;".into(),
-            '<' => "&lt;".into(),
-            '>' => "&gt;".into(),
-            '"' => "&quot;".into(),
-            '\'' => "&#39;".into(),
-            _ => c.to_string(),
-        })
-        .collect()
-}
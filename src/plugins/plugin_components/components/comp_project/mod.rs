@@ -0,0 +1,317 @@
+use async_trait::async_trait;
+
+use crate::plugins::{
+    plugin_components::{
+        ComponentContext, ComponentDescription, ComponentError, ComponentHandler, ComponentOutput, ComponentProps,
+        component_describe::{ArgKind, ArgSpec, ComponentExample},
+        html_escape,
+    },
+    plugin_showcase::{
+        ShowcaseData,
+        manager_list::{Node, ProjectStructure},
+    },
+};
+
+/// Card for one showcase project: image, name, description, state, tools and reading time.
+/// Props: { project }   (node path "/projects/game_design/x", "game_design/x" or a name)
+/// Args (legacy): [project]
+pub struct CompProjectCard {
+    data: ShowcaseData,
+}
+
+/// Grid of project cards for the children of a node (the top-level projects by default).
+/// Props: { path, limit }
+/// Args (legacy): [path, limit]
+pub struct CompProjectGrid {
+    data: ShowcaseData,
+}
+
+/// Nested link list of the project tree, or of the subtree under `path`.
+/// Props: { path, depth }
+/// Args (legacy): [path, depth]
+pub struct CompProjectTree {
+    data: ShowcaseData,
+}
+
+impl CompProjectCard {
+    pub fn new(data: ShowcaseData) -> Self {
+        Self { data }
+    }
+}
+
+impl CompProjectGrid {
+    pub fn new(data: ShowcaseData) -> Self {
+        Self { data }
+    }
+}
+
+impl CompProjectTree {
+    pub fn new(data: ShowcaseData) -> Self {
+        Self { data }
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for CompProjectCard {
+    fn component_name(&self) -> &'static str {
+        "projectCard"
+    }
+
+    fn describe(&self) -> Option<ComponentDescription> {
+        Some(ComponentDescription {
+            description: "Card linking to one showcase project".to_string(),
+            args: vec![
+                ArgSpec::new("project", ArgKind::String)
+                    .required()
+                    .describe("Project path (/projects/...) or name"),
+            ],
+            example: Some(ComponentExample {
+                compArgs: vec!["/projects/game_design/alchemists_convoy".to_string()],
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn component_parse(
+        &self,
+        _ctx: &ComponentContext,
+        _template: Option<String>,
+        args: Vec<String>,
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        let reference = arg(&props, &args, "project", 0).unwrap_or_default();
+        let structure = self.data.structure().map_err(structure_error)?;
+        let Some(node) = resolve_node(&structure, &reference) else {
+            return Err(ComponentError::NotFound(format!("project \"{reference}\"")));
+        };
+
+        let info = self.data.project_info(node).ok();
+        let mut html = String::from(r#"<article class="project-card">"#);
+        html.push_str(&format!(
+            r#"<a class="project-card-link" href="{}" onclick="return tm.handleLinkClick(event, this)">"#,
+            html_escape(&node_href(node))
+        ));
+        if let Some(image) = info.as_ref().and_then(|i| i.project_images.first()) {
+            html.push_str(&format!(
+                r#"<img class="project-card-image" src="{}" alt="" loading="lazy" decoding="async"/>"#,
+                html_escape(image)
+            ));
+        }
+        html.push_str(r#"<div class="project-card-body">"#);
+        let title = info
+            .as_ref()
+            .map(|i| i.project_name.as_str())
+            .filter(|n| !n.is_empty())
+            .unwrap_or(&node.name);
+        html.push_str(&format!(r#"<h3 class="project-card-title">{}</h3>"#, html_escape(title)));
+
+        if let Some(info) = &info {
+            if !info.project_description.is_empty() {
+                html.push_str(&format!(
+                    r#"<p class="project-card-description">{}</p>"#,
+                    html_escape(&info.project_description)
+                ));
+            }
+            let mut meta = Vec::new();
+            if !info.project_state.is_empty() {
+                meta.push(format!(
+                    r#"<span class="project-card-state">{}</span>"#,
+                    html_escape(&info.project_state)
+                ));
+            }
+            if !info.project_tools.is_empty() {
+                let tools: Vec<String> = info.project_tools.iter().map(|t| html_escape(t)).collect();
+                meta.push(format!(r#"<span class="project-card-tools">{}</span>"#, tools.join(", ")));
+            }
+            if info.project_stats.reading_minutes > 0 {
+                meta.push(format!(
+                    r#"<span class="project-card-reading">{} min read</span>"#,
+                    info.project_stats.reading_minutes
+                ));
+            }
+            if !meta.is_empty() {
+                html.push_str(r#"<p class="project-card-meta">"#);
+                html.push_str(&meta.join(" · "));
+                html.push_str("</p>");
+            }
+        }
+        html.push_str("</div></a></article>");
+
+        Ok(ComponentOutput::html(html))
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for CompProjectGrid {
+    fn component_name(&self) -> &'static str {
+        "projectGrid"
+    }
+
+    fn describe(&self) -> Option<ComponentDescription> {
+        Some(ComponentDescription {
+            description: "Project cards for the children of a showcase node".to_string(),
+            args: vec![
+                ArgSpec::new("path", ArgKind::String)
+                    .describe("Parent node path or name; top-level projects when empty"),
+                ArgSpec::new("limit", ArgKind::Number).describe("Maximum number of cards"),
+            ],
+            example: Some(ComponentExample {
+                compArgs: vec!["/projects/game_design".to_string()],
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn component_parse(
+        &self,
+        _ctx: &ComponentContext,
+        _template: Option<String>,
+        args: Vec<String>,
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        let structure = self.data.structure().map_err(structure_error)?;
+        let reference = arg(&props, &args, "path", 0).unwrap_or_default();
+        let nodes: &[Node] = if reference.trim().is_empty() {
+            structure.roots()
+        } else {
+            match resolve_node(&structure, &reference) {
+                Some(node) => &node.children,
+                None => {
+                    return Err(ComponentError::NotFound(format!("project \"{reference}\"")));
+                }
+            }
+        };
+        let limit = number_arg(&props, &args, "limit", 1).unwrap_or(nodes.len());
+
+        if nodes.is_empty() {
+            return Ok(ComponentOutput::html(
+                r#"<div class="project-grid"><p class="project-grid-empty">No projects yet.</p></div>"#,
+            ));
+        }
+
+        // Cards are nested components, so their markup and styles stay in one place. `ShowcaseData`
+        // caches the list and project infos, so each card only checks modification times.
+        let mut html = String::from(r#"<div class="project-grid">"#);
+        for node in nodes.iter().take(limit) {
+            let props = serde_json::json!({ "project": node.path }).to_string();
+            html.push_str(&format!(
+                r#"<smn-component name="projectCard" props="{}"></smn-component>"#,
+                html_escape(&props)
+            ));
+        }
+        html.push_str("</div>");
+        Ok(ComponentOutput::html(html))
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for CompProjectTree {
+    fn component_name(&self) -> &'static str {
+        "projectTree"
+    }
+
+    fn describe(&self) -> Option<ComponentDescription> {
+        Some(ComponentDescription {
+            description: "Nested list of links to showcase projects".to_string(),
+            args: vec![
+                ArgSpec::new("path", ArgKind::String)
+                    .describe("Root node path or name; the whole tree when empty"),
+                ArgSpec::new("depth", ArgKind::Number).describe("Levels to show (all when absent)"),
+            ],
+            example: Some(ComponentExample::default()),
+        })
+    }
+
+    async fn component_parse(
+        &self,
+        ctx: &ComponentContext,
+        _template: Option<String>,
+        args: Vec<String>,
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        let structure = self.data.structure().map_err(structure_error)?;
+        let reference = arg(&props, &args, "path", 0).unwrap_or_default();
+        let roots: &[Node] = if reference.trim().is_empty() {
+            structure.roots()
+        } else {
+            match resolve_node(&structure, &reference) {
+                Some(node) => std::slice::from_ref(node),
+                None => {
+                    return Err(ComponentError::NotFound(format!("project \"{reference}\"")));
+                }
+            }
+        };
+        let depth = number_arg(&props, &args, "depth", 1).unwrap_or(usize::MAX).max(1);
+
+        // The link to the page mounting the tree is marked as current
+        let current = ctx.page_path();
+        let mut html = String::from(r#"<nav class="project-tree">"#);
+        render_tree_level(roots, 0, depth, current.as_deref(), &mut html);
+        html.push_str("</nav>");
+        Ok(ComponentOutput::html(html))
+    }
+}
+
+fn render_tree_level(
+    nodes: &[Node],
+    level: usize,
+    max_depth: usize,
+    current: Option<&str>,
+    out: &mut String,
+) {
+    out.push_str(&format!(r#"<ul class="project-tree-list level-{level}">"#));
+    for node in nodes {
+        let is_current =
+            current.is_some_and(|p| p.trim_end_matches('/') == node.path.trim_end_matches('/'));
+        let (class, aria) = if is_current {
+            ("project-tree-link selected", r#" aria-current="page""#)
+        } else {
+            ("project-tree-link", "")
+        };
+        out.push_str(r#"<li class="project-tree-node">"#);
+        out.push_str(&format!(
+            r#"<a class="{class}" href="{}"{aria} onclick="return tm.handleLinkClick(event, this)">{}</a>"#,
+            html_escape(&node_href(node)),
+            html_escape(&node.name)
+        ));
+        if !node.children.is_empty() && level + 1 < max_depth {
+            render_tree_level(&node.children, level + 1, max_depth, current, out);
+        }
+        out.push_str("</li>");
+    }
+    out.push_str("</ul>");
+}
+
+/// Named prop first, then the legacy positional arg.
+fn arg(props: &ComponentProps, args: &[String], name: &str, index: usize) -> Option<String> {
+    props.get_str(name).or_else(|| args.get(index).cloned())
+}
+
+fn number_arg(props: &ComponentProps, args: &[String], name: &str, index: usize) -> Option<usize> {
+    props
+        .get_f64(name)
+        .or_else(|| args.get(index).and_then(|a| a.trim().parse().ok()))
+        .filter(|n| *n >= 0.0)
+        .map(|n| n as usize)
+}
+
+/// Accepts "/projects/a/b", "a/b" (relative to /projects) or a wiki-style reference.
+fn resolve_node<'a>(structure: &'a ProjectStructure, reference: &str) -> Option<&'a Node> {
+    let trimmed = reference.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    structure
+        .find_by_path(trimmed)
+        .or_else(|| structure.find_by_path(&format!("/projects/{}", trimmed.trim_start_matches('/'))))
+        .or_else(|| structure.find_by_reference(trimmed))
+}
+
+/// Project pages are linked with a trailing slash (relative assets resolve against it).
+fn node_href(node: &Node) -> String {
+    format!("{}/", node.path.trim_end_matches('/'))
+}
+
+fn structure_error(e: impl std::fmt::Display) -> ComponentError {
+    ComponentError::Render(format!("failed to load project structure: {e}"))
+}
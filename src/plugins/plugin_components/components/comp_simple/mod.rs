@@ -1,9 +1,11 @@
 use async_trait::async_trait;
-use hyper::{Body, Response, StatusCode, header::{CONTENT_TYPE, HeaderValue}};
-use std::{convert::Infallible, path::PathBuf};
+use std::path::{Path, PathBuf};
 use tokio::{fs::File, io::AsyncReadExt};
 
-use crate::plugins::plugin_components::{ComponentHandler, respond_status};
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentDescription, ComponentError, ComponentHandler, ComponentOutput, ComponentProps,
+    component_describe::ComponentExample,
+};
 
 /// A minimal component that just returns static HTML.
 /// - `name`: route name (e.g. "underConstruction")
@@ -25,49 +27,39 @@ impl ComponentHandler for SimpleTemplateComponent {
         self.name
     }
 
+    fn template_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn describe(&self) -> Option<ComponentDescription> {
+        Some(ComponentDescription {
+            description: format!("Static HTML from {}", self.path.display()),
+            args: Vec::new(),
+            example: Some(ComponentExample::default()),
+        })
+    }
+
     async fn component_parse(
         &self,
+        _ctx: &ComponentContext,
         // If the loader already found a template by component name, prefer it.
         template: Option<String>,
         _args: Vec<String>,
-    ) -> Result<Response<Body>, Infallible> {
+        _props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
         // 1) Use the template provided by the loader if available
         if let Some(tpl) = template {
-            let mut resp = Response::new(Body::from(tpl));
-            resp.headers_mut().insert(
-                CONTENT_TYPE,
-                HeaderValue::from_static("text/html; charset=utf-8"),
-            );
-            return Ok(resp);
+            return Ok(ComponentOutput::html(tpl));
         }
 
         // 2) Otherwise read the file we were registered with
         match File::open(&self.path).await {
             Ok(mut f) => {
                 let mut buf = Vec::new();
-                if let Err(_) = f.read_to_end(&mut buf).await {
-                    let mut r = respond_status(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read component file");
-                    r.headers_mut().insert(
-                        CONTENT_TYPE,
-                        HeaderValue::from_static("text/plain; charset=utf-8"),
-                    );
-                    return Ok(r);
-                }
-                let mut resp = Response::new(Body::from(buf));
-                resp.headers_mut().insert(
-                    CONTENT_TYPE,
-                    HeaderValue::from_static("text/html; charset=utf-8"),
-                );
-                Ok(resp)
-            }
-            Err(_) => {
-                let mut r = respond_status(StatusCode::NOT_FOUND, "Component file not found");
-                r.headers_mut().insert(
-                    CONTENT_TYPE,
-                    HeaderValue::from_static("text/plain; charset=utf-8"),
-                );
-                Ok(r)
+                f.read_to_end(&mut buf).await?;
+                Ok(ComponentOutput::html(String::from_utf8_lossy(&buf)))
             }
+            Err(_) => Err(ComponentError::TemplateMissing(self.path.display().to_string())),
         }
     }
 }
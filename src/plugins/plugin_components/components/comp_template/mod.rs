@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentDescription, ComponentError, ComponentHandler, ComponentOutput, ComponentProps,
+    component_describe::ComponentExample,
+    component_manifest::ManifestArg,
+    html_escape, template_engine,
+};
+
+/// A component declared in `components/components.json`.
+/// Each declared arg replaces `{{arg_name}}` in the template (HTML-escaped), taking its value from
+/// `compProps[arg_name]`, then the positional `compArgs` slot, then the manifest default.
+pub struct TemplatedComponent {
+    name: &'static str,
+    path: PathBuf,
+    args: Vec<ManifestArg>,
+    description: String,
+    example: Option<ComponentExample>,
+}
+
+impl TemplatedComponent {
+    pub fn new(name_static: &'static str, path: PathBuf, args: Vec<ManifestArg>) -> Self {
+        Self { name: name_static, path, args, description: String::new(), example: None }
+    }
+
+    /// Manifest `description` / `example` for `describe()`.
+    pub fn with_description(mut self, description: String, example: Option<ComponentExample>) -> Self {
+        self.description = description;
+        self.example = example;
+        self
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for TemplatedComponent {
+    fn component_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn template_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn describe(&self) -> Option<ComponentDescription> {
+        let args = self.args.iter().map(ManifestArg::spec).collect();
+        let description = if self.description.is_empty() {
+            format!("Template {}", self.path.display())
+        } else {
+            self.description.clone()
+        };
+        Some(ComponentDescription {
+            description,
+            args,
+            example: Some(self.example.clone().unwrap_or_default()),
+        })
+    }
+
+    async fn component_parse(
+        &self,
+        _ctx: &ComponentContext,
+        // If the loader already found a template by component name, prefer it.
+        template: Option<String>,
+        args: Vec<String>,
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        let tpl = match template {
+            Some(t) => t,
+            None => match tokio::fs::read_to_string(&self.path).await {
+                Ok(t) => t,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(ComponentError::TemplateMissing(self.path.display().to_string()));
+                }
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        let html = template_engine::substitute(&tpl, |name| {
+            let i = self.args.iter().position(|arg| arg.name == name)?;
+            let value = props
+                .get_str(name)
+                .or_else(|| args.get(i).cloned())
+                .unwrap_or_else(|| self.args[i].default.clone());
+            Some(html_escape(&value))
+        });
+        Ok(ComponentOutput::html(html))
+    }
+}
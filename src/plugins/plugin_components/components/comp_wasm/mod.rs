@@ -0,0 +1,429 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant, SystemTime},
+};
+use wasmi::{
+    Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, TrapCode, TypedFunc,
+    TypedResumableCall, WasmParams, WasmResults,
+};
+
+use crate::plugins::plugin_components::{
+    ComponentContext, ComponentError, ComponentHandler, ComponentOutput, ComponentProps,
+};
+
+// Linear memory a module may grow to.
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+// Instructions (roughly) a single render may execute.
+const FUEL_LIMIT: u64 = 50_000_000;
+// Fuel handed out at a time; the deadline is checked between slices.
+const FUEL_SLICE: u64 = 1_000_000;
+// Wall-clock time a single render may take.
+const TIME_LIMIT: Duration = Duration::from_millis(500);
+// Largest HTML a module may return.
+const OUTPUT_LIMIT: usize = 1024 * 1024;
+// Function table entries a module may have (one table).
+const TABLE_LIMIT: usize = 10_000;
+
+/// A component implemented by a sandboxed WebAssembly module (`components/<name>.wasm`).
+/// Modules get no imports at all (no WASI: no filesystem, network or clock), run with
+/// `MEMORY_LIMIT` bytes of memory, one table of `TABLE_LIMIT` entries, `FUEL_LIMIT` fuel and
+/// `TIME_LIMIT`, and get a fresh instance per request.
+///
+/// ABI (all exports required):
+/// - `memory`: the module's linear memory
+/// - `smn_alloc(len: i32) -> i32`: reserve `len` bytes for the input, return the pointer
+/// - `smn_render(ptr: i32, len: i32) -> i64`: render the UTF-8 JSON input at `ptr`
+///   (`{"name", "template", "args", "props", "locale", "path"}`) and return the HTML
+///   location packed as `(ptr << 32) | len`. A trap fails the render.
+///
+/// The compiled module is reused until the file's modification time changes.
+pub struct WasmComponent {
+    name: &'static str,
+    path: PathBuf,
+    module: Mutex<Option<(Option<SystemTime>, Module)>>,
+}
+
+/// Input handed to `smn_render`.
+#[derive(Serialize)]
+struct RenderInput<'a> {
+    name: &'a str,
+    template: Option<&'a str>,
+    args: &'a [String],
+    props: &'a Map<String, Value>,
+    locale: &'a str,
+    /// Page the component is rendered for, when known
+    path: Option<&'a str>,
+}
+
+impl WasmComponent {
+    pub fn new(name_static: &'static str, path: PathBuf) -> Self {
+        Self { name: name_static, path, module: Mutex::new(None) }
+    }
+
+    /// Compiled module, recompiled when the file changed on disk.
+    async fn module(&self) -> Result<Module, ComponentError> {
+        let modified = match tokio::fs::metadata(&self.path).await {
+            Ok(md) => md.modified().ok(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ComponentError::TemplateMissing(self.path.display().to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if let Some((stamp, module)) = self.module.lock().expect("wasm module lock poisoned").as_ref()
+            && *stamp == modified
+        {
+            return Ok(module.clone());
+        }
+
+        let bytes = tokio::fs::read(&self.path).await?;
+        // Validation and compilation are CPU-bound and scale with the module size
+        let module = tokio::task::spawn_blocking(move || Module::new(engine(), &bytes))
+            .await
+            .map_err(|e| ComponentError::Render(format!("module compile task failed: {e}")))?
+            .map_err(|e| ComponentError::Render(format!("invalid module '{}': {e}", self.path.display())))?;
+        if let Some(import) = module.imports().next() {
+            return Err(ComponentError::Render(format!(
+                "module '{}' imports {}::{}, but components get no imports",
+                self.path.display(),
+                import.module(),
+                import.name()
+            )));
+        }
+        *self.module.lock().expect("wasm module lock poisoned") = Some((modified, module.clone()));
+        Ok(module)
+    }
+}
+
+/// Shared engine with fuel metering enabled.
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        Engine::new(&config)
+    })
+}
+
+/// Call `func`, refuelling in `FUEL_SLICE` steps until it returns, the total fuel runs out
+/// or `deadline` passes.
+fn call_limited<P: WasmParams, R: WasmResults>(
+    store: &mut Store<StoreLimits>,
+    func: &TypedFunc<P, R>,
+    params: P,
+    deadline: Instant,
+    fuel_left: &mut u64,
+) -> Result<R, String> {
+    let describe = |e: wasmi::Error| match e.as_trap_code() {
+        Some(TrapCode::OutOfFuel) => format!("fuel limit ({FUEL_LIMIT}) exhausted"),
+        _ => e.to_string(),
+    };
+    let mut refuel = |store: &mut Store<StoreLimits>| -> Result<(), String> {
+        if *fuel_left == 0 {
+            return Err(format!("fuel limit ({FUEL_LIMIT}) exhausted"));
+        }
+        if Instant::now() > deadline {
+            return Err(format!("time limit ({} ms) exceeded", TIME_LIMIT.as_millis()));
+        }
+        let slice = FUEL_SLICE.min(*fuel_left);
+        *fuel_left -= slice;
+        store.set_fuel(slice).map_err(|e| e.to_string())
+    };
+
+    refuel(store)?;
+    let mut call = func.call_resumable(&mut *store, params).map_err(describe)?;
+    loop {
+        match call {
+            TypedResumableCall::Finished(result) => {
+                // Return what this slice did not use
+                *fuel_left += store.get_fuel().unwrap_or(0);
+                return Ok(result);
+            }
+            TypedResumableCall::OutOfFuel(paused) => {
+                refuel(store)?;
+                call = paused.resume(&mut *store).map_err(describe)?;
+            }
+            // Unreachable: modules have no imports, so no host function can trap
+            TypedResumableCall::HostTrap(_) => return Err("unexpected host trap".to_string()),
+        }
+    }
+}
+
+/// Instantiate `module` in a fresh, limited store and run `smn_render` on `input`.
+fn run_module(module: &Module, input: &[u8]) -> Result<String, String> {
+    let deadline = Instant::now() + TIME_LIMIT;
+    let mut fuel_left = FUEL_LIMIT;
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MEMORY_LIMIT)
+        .memories(1)
+        .table_elements(TABLE_LIMIT)
+        .tables(1)
+        .instances(1)
+        .build();
+    let mut store: Store<StoreLimits> = Store::new(engine(), limits);
+    store.limiter(|limits| limits);
+    // Start functions run on the first slice
+    store.set_fuel(FUEL_SLICE).map_err(|e| e.to_string())?;
+    fuel_left -= FUEL_SLICE;
+
+    let instance = Linker::<StoreLimits>::new(engine())
+        .instantiate_and_start(&mut store, module)
+        .map_err(|e| e.to_string())?;
+    fuel_left += store.get_fuel().unwrap_or(0);
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or("module does not export 'memory'")?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "smn_alloc")
+        .map_err(|e| format!("smn_alloc: {e}"))?;
+    let render = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "smn_render")
+        .map_err(|e| format!("smn_render: {e}"))?;
+
+    let len = i32::try_from(input.len()).map_err(|_| "input too large")?;
+    let ptr = call_limited(&mut store, &alloc, len, deadline, &mut fuel_left)?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input)
+        .map_err(|e| format!("smn_alloc returned an invalid pointer: {e}"))?;
+
+    let packed = call_limited(&mut store, &render, (ptr, len), deadline, &mut fuel_left)? as u64;
+    let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    if out_len > OUTPUT_LIMIT {
+        return Err(format!("output of {out_len} bytes exceeds the {OUTPUT_LIMIT} byte limit"));
+    }
+    let mut out = vec![0; out_len];
+    memory
+        .read(&store, out_ptr, &mut out)
+        .map_err(|e| format!("smn_render returned an invalid range: {e}"))?;
+    String::from_utf8(out).map_err(|_| "output is not valid UTF-8".to_string())
+}
+
+#[async_trait]
+impl ComponentHandler for WasmComponent {
+    fn component_name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn component_parse(
+        &self,
+        ctx: &ComponentContext,
+        template: Option<String>,
+        args: Vec<String>,
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError> {
+        let module = self.module().await?;
+        let page = ctx.page_path();
+        let input = serde_json::to_vec(&RenderInput {
+            name: self.name,
+            template: template.as_deref(),
+            args: &args,
+            props: props.as_map(),
+            locale: &ctx.locale,
+            path: page.as_deref(),
+        })
+        .map_err(|e| ComponentError::Render(e.to_string()))?;
+
+        // Fuel bounds the run time; keep it off the async workers regardless.
+        let html = tokio::task::spawn_blocking(move || run_module(&module, &input))
+            .await
+            .map_err(|e| ComponentError::Render(format!("module task failed: {e}")))?
+            .map_err(|e| ComponentError::Render(format!("module '{}': {e}", self.path.display())))?;
+        Ok(ComponentOutput::html(html))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- a minimal WebAssembly binary encoder ---
+
+    fn uleb(mut n: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn sleb(mut n: i64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn name(s: &str) -> Vec<u8> {
+        [uleb(s.len() as u64), s.as_bytes().to_vec()].concat()
+    }
+
+    fn vector(items: Vec<Vec<u8>>) -> Vec<u8> {
+        [uleb(items.len() as u64), items.concat()].concat()
+    }
+
+    fn section(id: u8, body: Vec<u8>) -> Vec<u8> {
+        [vec![id], uleb(body.len() as u64), body].concat()
+    }
+
+    fn i32_const(n: i32) -> Vec<u8> {
+        [vec![0x41], sleb(n.into())].concat()
+    }
+
+    fn i64_const(n: i64) -> Vec<u8> {
+        [vec![0x42], sleb(n)].concat()
+    }
+
+    /// Function body without locals; `end` is appended.
+    fn body(code: Vec<u8>) -> Vec<u8> {
+        let inner = [vec![0x00], code, vec![0x0b]].concat();
+        [uleb(inner.len() as u64), inner].concat()
+    }
+
+    /// The component ABI: `memory`, `smn_alloc` and `smn_render` with the given bodies.
+    struct Wasm {
+        alloc: Vec<u8>,
+        render: Vec<u8>,
+        memory_pages: u32,
+        table_size: Option<u32>,
+        import: bool,
+        data: Option<(i32, &'static [u8])>,
+    }
+
+    impl Wasm {
+        fn new(render: Vec<u8>) -> Self {
+            Wasm { alloc: i32_const(0), render, memory_pages: 1, table_size: None, import: false, data: None }
+        }
+
+        fn bytes(&self) -> Vec<u8> {
+            let first = u32::from(self.import);
+            let mut m = b"\0asm\x01\0\0\0".to_vec();
+            m.extend(section(1, vector(vec![
+                vec![0x60, 1, 0x7f, 1, 0x7f],       // (i32) -> i32
+                vec![0x60, 2, 0x7f, 0x7f, 1, 0x7e], // (i32, i32) -> i64
+                vec![0x60, 0, 0],                   // () -> ()
+            ])));
+            if self.import {
+                m.extend(section(2, vector(vec![[name("env"), name("f"), vec![0x00, 2]].concat()])));
+            }
+            m.extend(section(3, vector(vec![vec![0], vec![1]])));
+            if let Some(size) = self.table_size {
+                m.extend(section(4, vector(vec![[vec![0x70, 0x00], uleb(size.into())].concat()])));
+            }
+            m.extend(section(5, vector(vec![[vec![0x00], uleb(self.memory_pages.into())].concat()])));
+            m.extend(section(7, vector(vec![
+                [name("memory"), vec![0x02, 0]].concat(),
+                [name("smn_alloc"), vec![0x00], uleb(first.into())].concat(),
+                [name("smn_render"), vec![0x00], uleb((first + 1).into())].concat(),
+            ])));
+            m.extend(section(10, vector(vec![body(self.alloc.clone()), body(self.render.clone())])));
+            if let Some((offset, bytes)) = self.data {
+                let init = [vec![0x00], i32_const(offset), vec![0x0b], uleb(bytes.len() as u64), bytes.to_vec()];
+                m.extend(section(11, vector(vec![init.concat()])));
+            }
+            m
+        }
+
+        fn run(&self) -> Result<String, String> {
+            let module = Module::new(engine(), self.bytes()).map_err(|e| format!("compile: {e}"))?;
+            run_module(&module, br#"{"name":"t"}"#)
+        }
+    }
+
+    fn packed(ptr: u32, len: u32) -> Vec<u8> {
+        i64_const(((u64::from(ptr) << 32) | u64::from(len)) as i64)
+    }
+
+    // --- sandbox behaviour ---
+
+    #[test]
+    fn returns_html_from_linear_memory() {
+        let wasm = Wasm { data: Some((1024, b"<p>hi</p>")), ..Wasm::new(packed(1024, 9)) };
+        assert_eq!(wasm.run().unwrap(), "<p>hi</p>");
+    }
+
+    #[test]
+    fn infinite_loop_traps() {
+        // loop { br 0 }
+        let wasm = Wasm::new([vec![0x03, 0x40, 0x0c, 0x00, 0x0b], i64_const(0)].concat());
+        let started = Instant::now();
+        let err = wasm.run().unwrap_err();
+        assert!(err.contains("fuel limit") || err.contains("time limit"), "{err}");
+        assert!(started.elapsed() < TIME_LIMIT * 4, "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn memory_growth_is_capped() {
+        // if memory.grow(pages) == -1 { unreachable }
+        let grow = |pages: i32| {
+            let check = vec![0x46, 0x04, 0x40, 0x00, 0x0b]; // i32.eq, if { unreachable }
+            Wasm::new([i32_const(pages), vec![0x40, 0x00], i32_const(-1), check, packed(0, 0)].concat())
+        };
+        assert_eq!(grow(1).run().unwrap(), "");
+        let limit_pages = (MEMORY_LIMIT / 65536) as i32;
+        let err = grow(limit_pages).run().unwrap_err();
+        assert!(err.contains("unreachable"), "{err}");
+
+        let too_big = Wasm { memory_pages: limit_pages as u32 + 1, ..Wasm::new(packed(0, 0)) };
+        assert!(too_big.run().is_err());
+    }
+
+    #[test]
+    fn tables_are_capped() {
+        let ok = Wasm { table_size: Some(16), ..Wasm::new(packed(0, 0)) };
+        assert_eq!(ok.run().unwrap(), "");
+        let too_big = Wasm { table_size: Some(TABLE_LIMIT as u32 + 1), ..Wasm::new(packed(0, 0)) };
+        assert!(too_big.run().is_err());
+    }
+
+    #[test]
+    fn out_of_range_pointers_are_errors() {
+        let err = Wasm::new(packed(0xffff_0000, 16)).run().unwrap_err();
+        assert!(err.contains("invalid range"), "{err}");
+        let err = Wasm::new(packed(65_530, 100)).run().unwrap_err();
+        assert!(err.contains("invalid range"), "{err}");
+        let err = Wasm::new(packed(0, OUTPUT_LIMIT as u32 + 1)).run().unwrap_err();
+        assert!(err.contains("byte limit"), "{err}");
+
+        let bad_alloc = Wasm { alloc: i32_const(-1), ..Wasm::new(packed(0, 0)) };
+        let err = bad_alloc.run().unwrap_err();
+        assert!(err.contains("smn_alloc returned an invalid pointer"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn modules_with_imports_are_rejected() {
+        let dir = std::env::temp_dir().join(format!("smn_comp_wasm_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("importer.wasm");
+        std::fs::write(&path, Wasm { import: true, ..Wasm::new(packed(0, 0)) }.bytes()).unwrap();
+
+        let component = WasmComponent::new("importer", path.clone());
+        match component.module().await {
+            Err(ComponentError::Render(msg)) => assert!(msg.contains("imports env::f"), "{msg}"),
+            other => panic!("expected a render error, got {:?}", other.map(|_| ())),
+        }
+
+        std::fs::write(&path, Wasm::new(packed(0, 0)).bytes()).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        assert!(component.module().await.is_ok());
+
+        std::fs::write(&path, b"not wasm").unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(20)).unwrap();
+        assert!(matches!(component.module().await, Err(ComponentError::Render(m)) if m.contains("invalid module")));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,2 +1,6 @@
 pub mod comp_simple;
-pub mod comp_header;
\ No newline at end of file
+pub mod comp_header;
+pub mod comp_template;
+pub mod comp_project;
+pub mod comp_data;
+pub mod comp_wasm;
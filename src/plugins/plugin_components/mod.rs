@@ -1,21 +1,50 @@
 use async_trait::async_trait;
 use hyper::{
     body::to_bytes,
-    header::{CONTENT_TYPE, HeaderValue},
-    Body, Method, Request, Response, StatusCode,
+    header::{CONTENT_TYPE, HeaderValue, ORIGIN},
+    Body, HeaderMap, Method, Request, Response, StatusCode,
 };
-use serde::Deserialize;
-use smn_web_core::structs::struct_plugin::Plugin;
+use serde::{Deserialize, Serialize};
+use smn_web_core::{structs::struct_plugin::Plugin, systems::sys_plugin::BroadcastTx};
 use std::{
-    collections::HashMap,
     convert::Infallible,
     path::{Path, PathBuf},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Duration,
 };
-use tokio::{fs::File, io::AsyncReadExt};
-
-use crate::plugins::plugin_components::components::comp_simple::SimpleTemplateComponent;
 
+use crate::plugins::plugin_components::components::{
+    comp_data::DataComponent,
+    comp_project::{CompProjectCard, CompProjectGrid, CompProjectTree},
+    comp_simple::SimpleTemplateComponent,
+    comp_template::TemplatedComponent,
+    comp_wasm::WasmComponent,
+};
+use crate::plugins::plugin_showcase::ShowcaseData;
+use crate::systems::{sys_path::{self, PathError}, sys_static};
+
+pub mod component_batch;
+pub mod component_context;
+pub mod component_cors;
+pub mod component_describe;
+pub mod component_error;
+pub mod component_gallery;
+pub mod component_include;
+pub mod component_manifest;
+pub mod component_output;
+pub mod component_props;
+pub mod component_registry;
+pub mod component_style;
 pub mod components;
+pub mod template_engine;
+
+pub use component_context::ComponentContext;
+pub use component_cors::CorsConfig;
+pub use component_describe::ComponentDescription;
+pub use component_error::ComponentError;
+pub use component_output::{CacheHint, ComponentOutput};
+pub use component_props::ComponentProps;
+use component_registry::{ComponentRegistry, ComponentSource};
 
 // ---------------------- Component system ----------------------
 
@@ -24,43 +53,158 @@ pub trait ComponentHandler: Send + Sync {
     /// Programmatic name for routing: e.g. "simple_button"
     fn component_name(&self) -> &'static str;
 
+    /// Template file this handler renders from, if any. Cached alongside
+    /// `<name>/template.html` / `<name>.html` so requests never read it from disk.
+    fn template_path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Description, arguments and an example for `/components/_index`.
+    /// Described components also get their request arguments validated (400 on mismatch).
+    /// Called once, when the handler is registered.
+    fn describe(&self) -> Option<ComponentDescription> {
+        None
+    }
+
     /// Process the component request using the (optional) template contents and args.
-    /// Return a full HTTP response (set Content-Type as appropriate).
+    /// `ctx` describes the request (path, headers, cookies, locale, dev mode, plugin context),
+    /// `args` is the legacy positional `compArgs` list, `props` the named `compProps`.
+    /// `PluginComponents` turns the output into the response (headers, scoped style, includes).
     async fn component_parse(
         &self,
+        ctx: &ComponentContext,
         template: Option<String>,
         args: Vec<String>,
-    ) -> Result<Response<Body>, Infallible>;
+        props: ComponentProps,
+    ) -> Result<ComponentOutput, ComponentError>;
 }
 
 // ---------------------- Plugin ----------------------
 
+// Component templates and static assets live here.
+const COMPONENTS_DIR: &str = "./components";
+// Where data-driven components are declared.
+const MANIFEST_PATH: &str = "./components/components.json";
+// Root for the JSON files bound by manifest `data` entries.
+const DATA_DIR: &str = "./data";
+// How often the components directory is rescanned for added/removed templates.
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(2);
+// Request header carrying the admin key. Never taken from the URL, where it would end up in
+// access logs, browser history and `Referer` headers.
+const ADMIN_KEY_HEADER: &str = "x-admin-key";
+// Set on component responses in development mode: tells `componentManager.js` to track
+// mounted slots and re-mount them on `/events` updates.
+pub const HOT_RELOAD_HEADER: &str = "x-component-hot-reload";
+
 pub struct PluginComponents {
-    handlers: HashMap<&'static str, Box<dyn ComponentHandler>>,
+    /// Shared with the discovery task, which adds and removes components while serving.
+    handlers: Arc<RwLock<ComponentRegistry>>,
+    /// Enables developer tooling such as `/components/_gallery` for everyone.
+    dev_mode: bool,
+    /// Unlocks developer tooling outside of development mode.
+    admin_key: Option<String>,
+    /// `/events` bus: the watcher announces reloaded components here (hot reload).
+    broadcast: Option<BroadcastTx>,
+    /// Which other sites may fetch components (same-origin only by default).
+    cors: CorsConfig,
 }
 
 impl PluginComponents {
-    /// Default constructor: empty registry.
+    /// Default constructor: empty registry, production mode, no admin key, same-origin only.
     pub fn new() -> Self {
         Self {
-            handlers: HashMap::new(),
+            handlers: Arc::new(RwLock::new(ComponentRegistry::default())),
+            dev_mode: false,
+            admin_key: None,
+            broadcast: None,
+            cors: CorsConfig::same_origin(),
         }
     }
 
+    /// Development mode: developer endpoints are open to every request.
+    pub fn with_dev_mode(mut self, dev_mode: bool) -> Self {
+        self.dev_mode = dev_mode;
+        self
+    }
+
+    /// Admin key that unlocks developer endpoints (empty keys are ignored).
+    pub fn with_admin_key(mut self, key: Option<String>) -> Self {
+        self.admin_key = key.filter(|k| !k.is_empty());
+        self
+    }
+
+    /// Announce changed templates / styles as `update` events (data: component name) on this bus,
+    /// so `componentManager.js` can re-mount them. Use `PluginManager::broadcast()`.
+    pub fn with_broadcast(mut self, broadcast: BroadcastTx) -> Self {
+        self.broadcast = Some(broadcast);
+        self
+    }
+
+    /// Let other sites embed components: CORS headers on `/components/**` responses and
+    /// answers to `OPTIONS` preflights. See `CorsConfig::from_config`.
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = cors;
+        self
+    }
+
     /// Register a handler. Call this from `plugin_init`.
     pub fn register<H: ComponentHandler + 'static>(&mut self, handler: H) {
-        self.handlers.insert(handler.component_name(), Box::new(handler));
+        self.registry_mut().insert(Arc::new(handler), ComponentSource::Code);
+    }
+
+    /// Handle for expanding `<smn-component>` markers in other plugins' HTML responses.
+    pub fn includes(&self) -> component_include::ComponentIncludes {
+        component_include::ComponentIncludes::new(self.handlers.clone(), self.dev_mode)
+    }
+
+    fn registry(&self) -> RwLockReadGuard<'_, ComponentRegistry> {
+        self.handlers.read().expect("component registry lock poisoned")
+    }
+
+    fn registry_mut(&self) -> RwLockWriteGuard<'_, ComponentRegistry> {
+        self.handlers.write().expect("component registry lock poisoned")
+    }
+}
+
+impl Default for PluginComponents {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl Plugin for PluginComponents {
     async fn plugin_init(&mut self) {
+        // Data-driven components from the manifest (missing manifest = nothing to add)
+        let manifest_path = Path::new(MANIFEST_PATH);
+        if manifest_path.is_file() {
+            match component_manifest::load_manifest(manifest_path) {
+                Ok(manifest) => {
+                    for entry in manifest.components {
+                        self.register_templated(entry);
+                    }
+                }
+                Err(e) => eprintln!("WARN: Failed to load component manifest '{}': {e}", MANIFEST_PATH),
+            }
+        }
+
+        // Every template in the components directory, then keep rescanning while serving
+        sync_discovered(&self.handlers);
+        refresh_templates(&self.handlers);
+        tokio::spawn(watch_components_dir(self.handlers.clone(), self.broadcast.clone()));
+
+        let registry = self.registry();
         println!(
             "{} initialized with {} handler(s)",
             self.plugin_name(),
-            self.handlers.len()
+            registry.len()
         );
+        for (name, source) in registry.list() {
+            println!("  /components/{name} ({source})");
+        }
+        if self.cors.enabled() {
+            println!("  CORS origins: {}", self.cors.allowed_origins.join(", "));
+        }
     }
 
     fn plugin_name(&self) -> &str {
@@ -71,7 +215,21 @@ impl Plugin for PluginComponents {
         req.uri().path().starts_with("/components")
     }
 
-    async fn plugin_handle(&self, req: Request<Body>, _ctx: &smn_web_core::structs::struct_plugin::PluginContext) -> Result<Response<Body>, Infallible> {
+    async fn plugin_handle(&self, req: Request<Body>, plugin_ctx: &smn_web_core::structs::struct_plugin::PluginContext) -> Result<Response<Body>, Infallible> {
+        if req.method() == Method::OPTIONS {
+            return Ok(self.cors.preflight(req.headers()));
+        }
+        let origin = req.headers().get(ORIGIN).cloned();
+        let mut response = self.handle_request(req, plugin_ctx).await?;
+        self.cors.apply(origin.as_ref(), &mut response);
+        Ok(response)
+    }
+}
+
+impl PluginComponents {
+    /// Routes `/components/**` (everything but CORS, which `plugin_handle` adds).
+    async fn handle_request(&self, req: Request<Body>, plugin_ctx: &smn_web_core::structs::struct_plugin::PluginContext) -> Result<Response<Body>, Infallible> {
+        let ctx = ComponentContext::from_request(&req, plugin_ctx, self.dev_mode);
         let method = req.method().clone();
         let path = req.uri().path().to_string(); // Extract path as String to avoid borrow after move
 
@@ -89,21 +247,37 @@ impl Plugin for PluginComponents {
             .map(|seg| seg.contains('.'))
             .unwrap_or(false);
 
+        // Reserved endpoints
+        if after_prefix == "_batch" {
+            return component_batch::handle_batch(self.includes(), &ctx, &method, req).await;
+        }
+        if let Some(file) = after_prefix.strip_prefix("_styles/") {
+            return Ok(self.style_response(file.trim_end_matches(".css")));
+        }
+        if after_prefix == "_index" {
+            return Ok(self.index_response());
+        }
+        if after_prefix == "_gallery" {
+            // Hidden unless allowed, like any unknown path
+            if !self.dev_tools_allowed(&req) {
+                return Ok(respond_status(StatusCode::NOT_FOUND, "404 Not Found"));
+            }
+            return Ok(component_gallery::gallery_response(&self.includes(), &ctx).await);
+        }
+
         // If it's a registered component path (no '.'), and we have a handler -> process
         if !is_root && !looks_like_file {
-            if let Some(seg) = after_prefix.split('/').next() {
-                if let Some(handler) = self.handlers.get(seg) {
-                    return process_component_request(handler, seg, &method, req).await;
-                }
+            let seg = after_prefix.split('/').next().unwrap_or_default();
+            let known = self.registry().get(seg).is_some();
+            if known {
+                return process_component_request(&self.includes(), &ctx, seg, &method, req).await;
             }
         }
 
         // Fallback: static file hosting
-        serve_static(after_prefix).await
+        serve_static(&method, req.headers(), after_prefix).await
     }
-}
 
-impl PluginComponents {
     /// Register a simple, no-logic component by pointing at an HTML file.
     /// The route name is derived from the file stem.
     /// Example: "./components/underConstruction.html" -> route "underConstruction"
@@ -115,73 +289,212 @@ impl PluginComponents {
             .and_then(|s| s.to_str())
             .expect("register_simple: could not derive component name from path (missing file stem)");
 
-        // Leak the name to get a &'static str (handlers live for program lifetime)
-        let leaked: &'static str = Box::leak(stem.to_string().into_boxed_str());
+        let name_static = component_registry::intern_name(stem);
 
         // Insert handler
-        let handler = SimpleTemplateComponent::new(leaked, pb);
-        self.handlers.insert(leaked, Box::new(handler));
+        let handler = SimpleTemplateComponent::new(name_static, pb);
+        self.registry_mut().insert(Arc::new(handler), ComponentSource::Code);
     }
-}
-
 
-// ---------------------- Static serving ----------------------
+    /// Register a placeholder-substituting component from a manifest entry.
+    /// The template path is relative to `./components`; an entry with `data` (relative to
+    /// `./data`) becomes a `DataComponent` rendering `{{#each}}` / `{{#if}}` over that file.
+    pub fn register_templated(&mut self, entry: component_manifest::ManifestEntry) {
+        let path = match manifest_path(COMPONENTS_DIR, &entry.template) {
+            Ok(path) => path,
+            Err(e) => {
+                eprintln!("WARN: Skipping component '{}': template '{}' must stay inside {COMPONENTS_DIR}: {e}", entry.name, entry.template);
+                return;
+            }
+        };
+        let handler: Arc<dyn ComponentHandler> = match &entry.data {
+            Some(data) => {
+                let data_path = match manifest_path(DATA_DIR, data) {
+                    Ok(data_path) => data_path,
+                    Err(e) => {
+                        eprintln!("WARN: Skipping component '{}': data path '{data}' must stay inside {DATA_DIR}: {e}", entry.name);
+                        return;
+                    }
+                };
+                let name_static = component_registry::intern_name(&entry.name);
+                Arc::new(
+                    DataComponent::new(name_static, path, data_path, entry.args)
+                        .with_description(entry.description, entry.example),
+                )
+            }
+            None => {
+                let name_static = component_registry::intern_name(&entry.name);
+                Arc::new(
+                    TemplatedComponent::new(name_static, path, entry.args)
+                        .with_description(entry.description, entry.example),
+                )
+            }
+        };
+        self.registry_mut().insert(handler, ComponentSource::Manifest);
+    }
 
-async fn serve_static(safe_rel_path: &str) -> Result<Response<Body>, Infallible> {
-    // Harden path traversal: reject any ".." segments
-    if safe_rel_path.split('/').any(|s| s == "..") {
-        return Ok(respond_status(
-            StatusCode::FORBIDDEN,
-            "403 Forbidden: invalid path",
-        ));
+    /// Developer endpoints: open in development mode, otherwise only with the admin key
+    /// in the `X-Admin-Key` header.
+    fn dev_tools_allowed(&self, req: &Request<Body>) -> bool {
+        if self.dev_mode {
+            return true;
+        }
+        let Some(expected) = &self.admin_key else {
+            return false;
+        };
+        req.headers()
+            .get(ADMIN_KEY_HEADER)
+            .is_some_and(|given| constant_time_eq(given.as_bytes(), expected.as_bytes()))
     }
 
-    // Map to "./components/<safe_rel_path>" (or directory index if empty)
-    let base = PathBuf::from("./components");
-    let target_path = if safe_rel_path.is_empty() {
-        base.join("index.html")
-    } else {
-        base.join(safe_rel_path)
-    };
+    /// Register the showcase-backed components: `projectCard`, `projectGrid` and `projectTree`.
+    /// `data` comes from `PluginShowcase::data()`.
+    pub fn register_showcase(&mut self, data: ShowcaseData) {
+        self.register(CompProjectCard::new(data.clone()));
+        self.register(CompProjectGrid::new(data.clone()));
+        self.register(CompProjectTree::new(data));
+    }
 
-    // If it's a directory, try index.html inside it
-    let final_path = if is_dir(&target_path).await {
-        target_path.join("index.html")
-    } else {
-        target_path
-    };
+    /// `/components/_styles/<name>.css`: the component's scoped `style.css`.
+    fn style_response(&self, name: &str) -> Response<Body> {
+        match self.registry().style(name) {
+            Some(css) => ok_with_type(css.into_bytes(), "text/css; charset=utf-8"),
+            None => respond_status(StatusCode::NOT_FOUND, "404 Not Found"),
+        }
+    }
 
-    // Try to open and return
-    if let Some((p, bytes)) = try_open(&final_path).await {
-        return Ok(ok_with_type(bytes, guess_content_type(&p)));
+    /// `/components/_index`: every registered component with its description, as JSON.
+    fn index_response(&self) -> Response<Body> {
+        let registry = self.registry();
+        let components: Vec<IndexEntry> = registry
+            .list()
+            .into_iter()
+            .map(|(name, source)| IndexEntry {
+                name,
+                route: format!("/components/{name}"),
+                source: source.to_string(),
+                description: registry.description(name).map(|d| (*d).clone()),
+            })
+            .collect();
+
+        let json = serde_json::to_string_pretty(&ComponentIndex { components })
+            .unwrap_or_else(|_| "{}".to_string());
+        let mut resp = Response::new(Body::from(json));
+        resp.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=utf-8"),
+        );
+        resp
     }
+}
 
-    Ok(respond_status(StatusCode::NOT_FOUND, "404 Not Found"))
+#[derive(Serialize)]
+struct ComponentIndex {
+    components: Vec<IndexEntry>,
 }
 
-async fn is_dir(path: &Path) -> bool {
-    match tokio::fs::metadata(path).await {
-        Ok(md) => md.is_dir(),
-        Err(_) => false,
+/// One component as listed by `/components/_index`.
+#[derive(Serialize)]
+struct IndexEntry {
+    name: &'static str,
+    route: String,
+    source: String,
+    /// Absent for handlers that don't implement `describe()`
+    #[serde(flatten)]
+    description: Option<ComponentDescription>,
+}
+
+// ---------------------- Discovery ----------------------
+
+/// Register a `WasmComponent` for every module and a `SimpleTemplateComponent` for every other
+/// template in the components directory, and drop discovered ones whose file disappeared.
+/// Returns the newly added names.
+fn sync_discovered(registry: &RwLock<ComponentRegistry>) -> Vec<String> {
+    let found = component_registry::discover_components(Path::new(COMPONENTS_DIR));
+    let (added, removed) = registry
+        .write()
+        .expect("component registry lock poisoned")
+        .sync_discovered(&found, |name, path| -> Arc<dyn ComponentHandler> {
+            if component_registry::is_wasm(&path) {
+                Arc::new(WasmComponent::new(name, path))
+            } else {
+                Arc::new(SimpleTemplateComponent::new(name, path))
+            }
+        });
+
+    for name in &added {
+        println!("Component registered: /components/{name}");
+    }
+    for name in removed {
+        println!("Component removed: /components/{name}");
     }
+    added
+}
+
+/// Preload / revalidate the template cache (by modification time).
+fn refresh_templates(registry: &RwLock<ComponentRegistry>) -> Vec<String> {
+    component_registry::refresh_templates(registry, Path::new(COMPONENTS_DIR))
 }
 
-async fn try_open(path: &Path) -> Option<(String, Vec<u8>)> {
-    if tokio::fs::metadata(path).await.ok()?.is_file() {
-        if let Ok(mut f) = File::open(path).await {
-            let mut contents = Vec::new();
-            if f.read_to_end(&mut contents).await.is_ok() {
-                return Some((path.to_string_lossy().into_owned(), contents));
+async fn watch_components_dir(registry: Arc<RwLock<ComponentRegistry>>, broadcast: Option<BroadcastTx>) {
+    let mut ticker = tokio::time::interval(DISCOVERY_INTERVAL);
+    ticker.tick().await; // first tick fires immediately; init already scanned
+    loop {
+        ticker.tick().await;
+        let added = sync_discovered(&registry);
+        // First loads of just-registered components are not reloads
+        for name in refresh_templates(&registry).into_iter().filter(|n| !added.contains(n)) {
+            println!("Component template reloaded: /components/{name}");
+            if let Some(broadcast) = &broadcast {
+                broadcast.send_update_id(name);
             }
         }
     }
-    None
+}
+
+
+/// A manifest path under `root`, checked by `sys_path::resolve_path`. Files that don't exist
+/// yet are allowed (they're reported when rendered); traversal, hidden names and symlinks out
+/// of `root` are not.
+fn manifest_path(root: &str, rel: &str) -> Result<PathBuf, PathError> {
+    match sys_path::resolve_path(Path::new(root), rel) {
+        Ok(_) | Err(PathError::NotFound) => Ok(PathBuf::from(root).join(rel.trim_start_matches('/'))),
+        Err(e) => Err(e),
+    }
+}
+
+// ---------------------- Static serving ----------------------
+
+/// Stream a file from the components directory (Range / HEAD aware, see `sys_static`).
+/// `raw_rel_path` is still percent-encoded; `sys_path` decodes and confines it to the directory.
+async fn serve_static(method: &Method, headers: &HeaderMap, raw_rel_path: &str) -> Result<Response<Body>, Infallible> {
+    let root = Path::new(COMPONENTS_DIR);
+    let resolved = sys_path::resolve_url_path(root, raw_rel_path).and_then(|p| {
+        // Directories (including the root) serve their index.html
+        if p.is_dir() { sys_path::resolve_path(&p, "index.html") } else { Ok(p) }
+    });
+    let final_path = match resolved {
+        Ok(p) => p,
+        Err(e) if e.is_not_found() => return Ok(respond_status(StatusCode::NOT_FOUND, "404 Not Found")),
+        Err(PathError::Io(e)) => {
+            eprintln!("WARN: Failed to resolve component asset '{raw_rel_path}': {e}");
+            return Ok(respond_status(StatusCode::NOT_FOUND, "404 Not Found"));
+        }
+        Err(_) => return Ok(respond_status(StatusCode::FORBIDDEN, "403 Forbidden: invalid path")),
+    };
+
+    if let Some(resp) = sys_static::serve_file(method, headers, &final_path).await {
+        return Ok(resp);
+    }
+
+    Ok(respond_status(StatusCode::NOT_FOUND, "404 Not Found"))
 }
 
 fn ok_with_type(body: Vec<u8>, content_type: &'static str) -> Response<Body> {
     let mut resp = Response::new(Body::from(body));
     resp.headers_mut()
         .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    sys_static::set_nosniff(resp.headers_mut());
     resp
 }
 
@@ -191,25 +504,23 @@ fn respond_status(code: StatusCode, msg: &str) -> Response<Body> {
     r
 }
 
-fn guess_content_type(path: &str) -> &'static str {
-    if let Some(ext) = Path::new(path).extension().and_then(|s| s.to_str()) {
-        match ext {
-            "html" | "htm" => "text/html; charset=utf-8",
-            "css" => "text/css; charset=utf-8",
-            "js" => "application/javascript; charset=utf-8",
-            "json" => "application/json; charset=utf-8",
-            "svg" => "image/svg+xml",
-            "png" => "image/png",
-            "jpg" | "jpeg" => "image/jpeg",
-            "gif" => "image/gif",
-            "webp" => "image/webp",
-            "wasm" => "application/wasm",
-            "txt" => "text/plain; charset=utf-8",
-            _ => "application/octet-stream",
-        }
-    } else {
-        "application/octet-stream"
-    }
+/// Compare secrets without short-circuiting on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Simple HTML escaper
+pub(crate) fn html_escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '&' => "&amp;".into(),
+            '<' => "&lt;".into(),
+            '>' => "&gt;".into(),
+            '"' => "&quot;".into(),
+            '\'' => "&#39;".into(),
+            _ => c.to_string(),
+        })
+        .collect()
 }
 
 // ---------------------- Component request plumbing ----------------------
@@ -219,46 +530,141 @@ fn guess_content_type(path: &str) -> &'static str {
 struct ComponentPayload {
     #[serde(default)]
     compArgs: Vec<String>,
+    #[serde(default)]
+    compProps: serde_json::Map<String, serde_json::Value>,
 }
 
 async fn process_component_request(
-    handler: &Box<dyn ComponentHandler>,
+    includes: &component_include::ComponentIncludes,
+    ctx: &ComponentContext,
     component_name: &str,
     method: &Method,
     mut req: Request<Body>,
 ) -> Result<Response<Body>, Infallible> {
-    // Load optional template file: ./components/<component_name>/template.html
-    // If not found, pass None to the handler.
-    let template_path_a = PathBuf::from("./components")
-        .join(component_name)
-        .join("template.html");
-    let template_path_b = PathBuf::from("./components").join(format!("{component_name}.html"));
-
-    let template = if let Some((_p, bytes)) = try_open(&template_path_a).await {
-        Some(String::from_utf8_lossy(&bytes).into_owned())
-    } else if let Some((_p, bytes)) = try_open(&template_path_b).await {
-        Some(String::from_utf8_lossy(&bytes).into_owned())
-    } else {
-        None
-    };
-
-    // Extract args: prefer POST JSON body { "compArgs": ["..."] }
-    let args = if *method == Method::POST {
+    // Extract args: prefer POST JSON body { "compArgs": ["..."], "compProps": { ... } }
+    let (args, props) = if *method == Method::POST {
         let full = to_bytes(req.body_mut()).await.unwrap_or_default();
         if full.is_empty() {
-            Vec::new()
+            (Vec::new(), ComponentProps::default())
         } else {
-            serde_json::from_slice::<ComponentPayload>(&full)
-                .map(|p| p.compArgs)
-                .unwrap_or_default()
+            match serde_json::from_slice::<ComponentPayload>(&full) {
+                Ok(p) => (p.compArgs, ComponentProps::new(p.compProps)),
+                Err(e) => {
+                    let err = ComponentError::BadArgs(vec![format!(
+                        "malformed JSON body, expected {{ \"compArgs\": [...], \"compProps\": {{...}} }}: {e}"
+                    )]);
+                    return Ok(err.into_response(component_name, ctx.dev_mode));
+                }
+            }
         }
     } else {
-        // Optional: allow GET ?compArgs=... (comma-separated) as a convenience
+        // Optional: allow GET ?compArgs=... (comma-separated) and ?prop.name=value as a convenience
         let query = req.uri().query().unwrap_or_default();
-        parse_args_from_query(query)
+        (parse_args_from_query(query), ComponentProps::from_query(query))
+    };
+
+    let mut resp = match run_component(includes, ctx, component_name, args, props).await {
+        Ok(output) => output.into_response(),
+        Err(e) => e.into_response(component_name, ctx.dev_mode),
+    };
+    if ctx.dev_mode {
+        resp.headers_mut().insert(HOT_RELOAD_HEADER, HeaderValue::from_static("on"));
+    }
+    Ok(resp)
+}
+
+/// Run a component by name: cached template, handler, scoped style, then nested includes.
+/// Unknown names are `NotFound`, arguments rejected by `describe()` are `BadArgs`.
+/// Server-side failures are logged here.
+pub(crate) async fn run_component(
+    includes: &component_include::ComponentIncludes,
+    ctx: &ComponentContext,
+    component_name: &str,
+    args: Vec<String>,
+    props: ComponentProps,
+) -> Result<ComponentOutput, ComponentError> {
+    let result = async {
+        let output = render_component(includes.registry(), ctx, component_name, args.clone(), props.clone()).await?;
+        expand_nested_components(output, includes, ctx, component_name, &args, &props).await
+    }
+    .await;
+
+    if let Err(e) = &result
+        && e.status().is_server_error()
+    {
+        eprintln!("Component '{component_name}' failed: {e}");
+    }
+    result
+}
+
+/// Scope the output's root element and link the component's `style.css`, if it has one.
+fn apply_component_style(
+    registry: &RwLock<ComponentRegistry>,
+    component_name: &str,
+    mut output: ComponentOutput,
+) -> ComponentOutput {
+    if !output.is_html() {
+        return output;
+    }
+    let version = registry
+        .read()
+        .expect("component registry lock poisoned")
+        .style_version(component_name);
+    if let Some(version) = version {
+        output.html = component_style::scope_root(&output.html, component_name);
+        output = output.with_stylesheet(format!("/components/_styles/{component_name}.css?v={version}"));
+    }
+    output
+}
+
+/// Resolve `<smn-component>` markers inside a component's HTML output before responding.
+/// A failing child, an include cycle or runaway nesting fails the parent (naming the chain, e.g. `a -> b -> a`).
+async fn expand_nested_components(
+    output: ComponentOutput,
+    includes: &component_include::ComponentIncludes,
+    ctx: &ComponentContext,
+    component_name: &str,
+    args: &[String],
+    props: &ComponentProps,
+) -> Result<ComponentOutput, ComponentError> {
+    if !output.is_html() || !component_include::ComponentIncludes::has_markers(&output.html) {
+        return Ok(output);
+    }
+
+    let children = includes
+        .expand_children(ctx, component_name, &output.html, args, props)
+        .await
+        .map_err(|e| ComponentError::Render(e.to_string()))?;
+    let mut output = output;
+    output.html = children.html.clone();
+    output.absorb_assets(&children);
+    Ok(output)
+}
+
+/// Run a registered component outside of an HTTP request (no nested includes).
+/// Arguments are validated against `describe()` exactly as for `/components/<name>` requests.
+pub(crate) async fn render_component(
+    registry: &RwLock<ComponentRegistry>,
+    ctx: &ComponentContext,
+    component_name: &str,
+    args: Vec<String>,
+    props: ComponentProps,
+) -> Result<ComponentOutput, ComponentError> {
+    let (handler, template, description) = {
+        let registry = registry.read().expect("component registry lock poisoned");
+        let (handler, template) = registry
+            .get_with_template(component_name)
+            .ok_or_else(|| ComponentError::NotFound(format!("component \"{component_name}\"")))?;
+        (handler, template, registry.description(component_name))
     };
 
-    handler.component_parse(template, args).await
+    // Same rule for requests and server-side includes
+    if let Some(description) = description {
+        description.validate(&args, &props).map_err(ComponentError::BadArgs)?;
+    }
+
+    let output = handler.component_parse(ctx, template, args, props).await?;
+    Ok(apply_component_style(registry, component_name, output))
 }
 
 fn parse_args_from_query(qs: &str) -> Vec<String> {
@@ -0,0 +1,394 @@
+use serde_json::{Map, Value};
+
+use crate::plugins::plugin_components::html_escape;
+
+/// Logic-light templates for data-bound components:
+/// - `{{path}}` value, HTML-escaped; `{{{path}}}` raw (trusted data only)
+/// - `{{#each path}}…{{else}}…{{/each}}` loop over an array (or an object's values);
+///   inside: `{{this}}`, `{{@index}}`, `{{@first}}`, `{{@last}}`, `{{@key}}`, `{{../path}}`
+/// - `{{#if path}}…{{else}}…{{/if}}`, `{{#unless path}}…{{/unless}}`
+///   (false, null, 0, "" and empty arrays / objects are falsy)
+/// - `{{! comment }}`
+///
+/// Paths are dotted (`link.href`, `items.0`) and resolve from the innermost loop item outwards.
+pub fn render(template: &str, root: &Value) -> Result<String, String> {
+    let nodes = parse(template)?;
+    let mut out = String::with_capacity(template.len());
+    render_nodes(&nodes, &Scope::root(root), &mut out);
+    Ok(out)
+}
+
+/// Plain `{{name}}` placeholders in one pass: names `value` knows are replaced, anything else
+/// is kept as written. Inserted values are never scanned again, so a value containing
+/// `{{other}}` stays literal.
+pub fn substitute(template: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}").and_then(|end| Some((end, value(&after[..end])?))) {
+            Some((end, v)) => {
+                out.push_str(&v);
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var { path: String, raw: bool },
+    Block { kind: BlockKind, path: String, body: Vec<Node>, otherwise: Vec<Node> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockKind {
+    Each,
+    If,
+    Unless,
+}
+
+impl BlockKind {
+    fn name(self) -> &'static str {
+        match self {
+            BlockKind::Each => "each",
+            BlockKind::If => "if",
+            BlockKind::Unless => "unless",
+        }
+    }
+}
+
+/// An open block while parsing; nodes go to `otherwise` after `{{else}}`.
+struct Frame {
+    kind: BlockKind,
+    path: String,
+    body: Vec<Node>,
+    otherwise: Vec<Node>,
+    in_else: bool,
+}
+
+fn parse(src: &str) -> Result<Vec<Node>, String> {
+    let mut root: Vec<Node> = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut rest = src;
+
+    fn push(root: &mut Vec<Node>, stack: &mut [Frame], node: Node) {
+        match stack.last_mut() {
+            Some(f) if f.in_else => f.otherwise.push(node),
+            Some(f) => f.body.push(node),
+            None => root.push(node),
+        }
+    }
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            push(&mut root, &mut stack, Node::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start..];
+
+        if let Some(inner) = after.strip_prefix("{{{") {
+            let end = inner.find("}}}").ok_or("unclosed {{{")?;
+            let path = inner[..end].trim().to_string();
+            push(&mut root, &mut stack, Node::Var { path, raw: true });
+            rest = &inner[end + 3..];
+            continue;
+        }
+
+        let inner = &after[2..];
+        let end = inner.find("}}").ok_or("unclosed {{")?;
+        let tag = inner[..end].trim();
+        rest = &inner[end + 2..];
+
+        if tag.starts_with('!') {
+            continue;
+        }
+        if let Some(open) = tag.strip_prefix('#') {
+            let (name, path) = open.split_once(char::is_whitespace).unwrap_or((open, ""));
+            let kind = match name {
+                "each" => BlockKind::Each,
+                "if" => BlockKind::If,
+                "unless" => BlockKind::Unless,
+                other => return Err(format!("unknown block {{{{#{other}}}}}")),
+            };
+            if path.trim().is_empty() {
+                return Err(format!("{{{{#{name}}}}} needs a path"));
+            }
+            stack.push(Frame {
+                kind,
+                path: path.trim().to_string(),
+                body: Vec::new(),
+                otherwise: Vec::new(),
+                in_else: false,
+            });
+        } else if tag == "else" {
+            let frame = stack.last_mut().ok_or("{{else}} outside of a block")?;
+            if frame.in_else {
+                return Err(format!("second {{{{else}}}} in {{{{#{} {}}}}}", frame.kind.name(), frame.path));
+            }
+            frame.in_else = true;
+        } else if let Some(close) = tag.strip_prefix('/') {
+            let frame = stack.pop().ok_or_else(|| format!("{{{{/{close}}}}} without an open block"))?;
+            if close.trim() != frame.kind.name() {
+                return Err(format!(
+                    "{{{{#{} {}}}}} closed by {{{{/{}}}}}",
+                    frame.kind.name(),
+                    frame.path,
+                    close.trim()
+                ));
+            }
+            let node = Node::Block {
+                kind: frame.kind,
+                path: frame.path,
+                body: frame.body,
+                otherwise: frame.otherwise,
+            };
+            push(&mut root, &mut stack, node);
+        } else {
+            push(&mut root, &mut stack, Node::Var { path: tag.to_string(), raw: false });
+        }
+    }
+    if !rest.is_empty() {
+        push(&mut root, &mut stack, Node::Text(rest.to_string()));
+    }
+    if let Some(open) = stack.last() {
+        return Err(format!("unclosed {{{{#{} {}}}}}", open.kind.name(), open.path));
+    }
+    Ok(root)
+}
+
+/// One level of the lookup chain: the root value or the current loop item.
+struct Scope<'a> {
+    value: &'a Value,
+    parent: Option<&'a Scope<'a>>,
+    index: Option<usize>,
+    len: usize,
+    key: Option<&'a str>,
+}
+
+impl<'a> Scope<'a> {
+    fn root(value: &'a Value) -> Self {
+        Self { value, parent: None, index: None, len: 0, key: None }
+    }
+
+    fn lookup(&self, path: &str) -> Option<Value> {
+        if let Some(up) = path.strip_prefix("../") {
+            return self.parent.unwrap_or(self).lookup(up);
+        }
+        match path {
+            "this" | "." => return Some(self.value.clone()),
+            "@index" => return self.index.map(Value::from),
+            "@first" => return self.index.map(|i| Value::Bool(i == 0)),
+            "@last" => return self.index.map(|i| Value::Bool(i + 1 == self.len)),
+            "@key" => return self.key.map(Value::from),
+            _ => {}
+        }
+
+        let mut segments = path.split('.');
+        let first = segments.next()?;
+        let start = if first == "this" {
+            self.value
+        } else {
+            // Innermost scope that has the first segment
+            let mut scope = Some(self);
+            loop {
+                let s = scope?;
+                if let Some(v) = child(s.value, first) {
+                    break v;
+                }
+                scope = s.parent;
+            }
+        };
+        segments.try_fold(start, child).cloned()
+    }
+}
+
+fn child<'v>(value: &'v Value, segment: &str) -> Option<&'v Value> {
+    match value {
+        Value::Object(map) => map.get(segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    }
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().is_some_and(|f| f != 0.0),
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(a)) => !a.is_empty(),
+        Some(Value::Object(o)) => !o.is_empty(),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn render_nodes(nodes: &[Node], scope: &Scope<'_>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(t) => out.push_str(t),
+            Node::Var { path, raw } => {
+                let text = scope.lookup(path).map(|v| to_text(&v)).unwrap_or_default();
+                if *raw {
+                    out.push_str(&text);
+                } else {
+                    out.push_str(&html_escape(&text));
+                }
+            }
+            Node::Block { kind: BlockKind::If, path, body, otherwise } => {
+                let branch = if truthy(scope.lookup(path).as_ref()) { body } else { otherwise };
+                render_nodes(branch, scope, out);
+            }
+            Node::Block { kind: BlockKind::Unless, path, body, otherwise } => {
+                let branch = if truthy(scope.lookup(path).as_ref()) { otherwise } else { body };
+                render_nodes(branch, scope, out);
+            }
+            Node::Block { kind: BlockKind::Each, path, body, otherwise } => {
+                let value = scope.lookup(path);
+                let items: Vec<(Option<&str>, &Value)> = match &value {
+                    Some(Value::Array(items)) => items.iter().map(|v| (None, v)).collect(),
+                    Some(Value::Object(map)) => map.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+                    _ => Vec::new(),
+                };
+                if items.is_empty() {
+                    render_nodes(otherwise, scope, out);
+                    continue;
+                }
+                let len = items.len();
+                for (i, (key, item)) in items.into_iter().enumerate() {
+                    let inner = Scope { value: item, parent: Some(scope), index: Some(i), len, key };
+                    render_nodes(body, &inner, out);
+                }
+            }
+        }
+    }
+}
+
+/// Root value for a data-bound component: the data file's top-level keys,
+/// overridden by the request's named args, plus `data` and `args` for explicit access.
+pub fn root_value(data: &Value, args: &Map<String, Value>) -> Value {
+    let mut root = match data {
+        Value::Object(map) => map.clone(),
+        _ => Map::new(),
+    };
+    for (k, v) in args {
+        root.insert(k.clone(), v.clone());
+    }
+    root.insert("data".to_string(), data.clone());
+    root.insert("args".to_string(), Value::Object(args.clone()));
+    Value::Object(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ok(template: &str, data: Value) -> String {
+        render(template, &data).unwrap_or_else(|e| panic!("{template:?} failed: {e}"))
+    }
+
+    #[test]
+    fn escapes_by_default_and_raw_only_with_triple_braces() {
+        let data = json!({ "x": "<b>\"Tom\" & 'Jerry'</b>" });
+        assert_eq!(ok("{{x}}", data.clone()), "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;");
+        assert_eq!(ok("{{ x }}", data.clone()), ok("{{x}}", data.clone()));
+        assert_eq!(ok("{{{x}}}", data), "<b>\"Tom\" & 'Jerry'</b>");
+    }
+
+    #[test]
+    fn values_are_not_rendered_as_templates() {
+        let data = json!({ "x": "{{y}}", "y": "secret" });
+        assert_eq!(ok("{{x}} {{{x}}}", data), "{{y}} {{y}}");
+    }
+
+    #[test]
+    fn nested_each_and_if() {
+        let data = json!({
+            "groups": [
+                { "name": "A", "items": [{ "label": "a1", "on": true }, { "label": "a2", "on": false }] },
+                { "name": "B", "items": [] }
+            ]
+        });
+        let template = "{{#each groups}}[{{name}}:{{#each items}}{{#if on}}+{{else}}-{{/if}}{{label}}{{#unless @last}},{{/unless}}{{/each}}]{{/each}}";
+        assert_eq!(ok(template, data), "[A:+a1,-a2][B:]");
+    }
+
+    #[test]
+    fn else_branches() {
+        let template = "{{#each list}}{{this}}{{else}}empty{{/each}}|{{#if flag}}yes{{else}}no{{/if}}|{{#unless flag}}off{{else}}on{{/unless}}";
+        assert_eq!(ok(template, json!({ "list": [], "flag": 0 })), "empty|no|off");
+        assert_eq!(ok(template, json!({ "list": [1, 2], "flag": "x" })), "12|yes|on");
+    }
+
+    #[test]
+    fn loop_metadata_and_objects() {
+        let data = json!({ "links": { "a": "x", "b": "y" } });
+        assert_eq!(
+            ok("{{#each links}}{{@index}}{{@key}}={{this}}{{#if @first}}!{{/if}};{{/each}}", data),
+            "0a=x!;1b=y;"
+        );
+    }
+
+    #[test]
+    fn parent_scope_and_past_the_root() {
+        let data = json!({ "title": "T", "items": [{ "title": "inner" }] });
+        assert_eq!(ok("{{#each items}}{{title}}/{{../title}}{{/each}}", data.clone()), "inner/T");
+        // `../` beyond the root stays at the root instead of failing
+        assert_eq!(ok("{{../title}}|{{../../title}}", data.clone()), "T|T");
+        assert_eq!(ok("{{#each items}}{{../../../title}}{{/each}}", data), "T");
+    }
+
+    #[test]
+    fn missing_values_render_empty() {
+        assert_eq!(ok("[{{nope}}][{{a.b.c}}][{{{nope}}}]", json!({ "a": 1 })), "[][][]");
+    }
+
+    #[test]
+    fn comments_are_dropped() {
+        assert_eq!(ok("a{{! note }}b", json!({})), "ab");
+    }
+
+    #[test]
+    fn malformed_templates_are_errors() {
+        for template in [
+            "{{#each items}}unclosed",
+            "{{#if a}}{{#each b}}{{/if}}{{/each}}",
+            "{{#if a}}x{{/each}}",
+            "{{/if}}",
+            "{{else}}",
+            "{{#if a}}x{{else}}y{{else}}z{{/if}}",
+            "{{#if}}x{{/if}}",
+            "{{#with a}}x{{/with}}",
+            "text {{unclosed",
+            "text {{{raw}}",
+        ] {
+            assert!(render(template, &json!({ "a": true })).is_err(), "{template:?} should fail");
+        }
+    }
+
+    #[test]
+    fn substitute_is_single_pass() {
+        let values = |name: &str| match name {
+            "title" => Some("{{message}}".to_string()),
+            "message" => Some("body".to_string()),
+            _ => None,
+        };
+        assert_eq!(substitute("<h4>{{title}}</h4><p>{{message}}</p>{{other}}", values), "<h4>{{message}}</h4><p>body</p>{{other}}");
+        assert_eq!(substitute("{{ {{title}}", values), "{{ {{message}}");
+    }
+}
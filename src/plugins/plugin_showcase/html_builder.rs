@@ -22,6 +22,7 @@ pub fn generate_project_page_html(
     path_relative: &str,
     info: &manager_project::ProjectInfo,
     md_text: &str,
+    backlinks: &[&manager_list::Node],
 ) -> String {
     let template = load_template();
     let sidebar = render_sidebar_html(project_structure, req_path, path_relative);
@@ -42,6 +43,23 @@ pub fn generate_project_page_html(
         ));
     }
 
+    // 2b) Reading metadata (words, reading time, last updated)
+    let stats = &info.project_stats;
+    let mut meta = Vec::new();
+    if stats.word_count > 0 {
+        meta.push(format!(r#"<span class="meta-words">{} words</span>"#, stats.word_count));
+        meta.push(format!(r#"<span class="meta-reading">{} min read</span>"#, stats.reading_minutes));
+    }
+    if let Some(updated) = &stats.last_updated {
+        let d = html_escape(updated);
+        meta.push(format!(r#"<span class="meta-updated">Updated <time datetime="{d}">{d}</time></span>"#));
+    }
+    if !meta.is_empty() {
+        content.push_str(r#"<p class="project-meta">"#);
+        content.push_str(&meta.join(r#"<span class="meta-sep"> · </span>"#));
+        content.push_str("</p>");
+    }
+
     // 3) State (little box)
     if !info.project_state.is_empty() {
         content.push_str(r#"<div class="state-box project-state">"#);
@@ -82,14 +100,14 @@ pub fn generate_project_page_html(
 
     // 6) Content (Markdown → HTML)
     if !md_text.trim().is_empty() {
-        let md_html = html_markdown::render_markdown(md_text);
+        let md_html = html_markdown::render_markdown(md_text, &info.project_markdown, project_structure);
         content.push_str(r#"<section class="project-content">"#);
         content.push_str(&md_html);
         content.push_str("</section>");
     }
 
-    // 7 & 8) Tools and Links (two columns)
-    if !info.project_tools.is_empty() || !info.project_links.is_empty() {
+    // 7, 8 & 9) Tools, Links and Referenced by (columns)
+    if !info.project_tools.is_empty() || !info.project_links.is_empty() || !backlinks.is_empty() {
         content.push_str(r#"<section class="meta-grid">"#);
 
         // Tools card
@@ -121,6 +139,23 @@ pub fn generate_project_page_html(
             content.push_str(r#"</tbody></table></div>"#);
         }
 
+        // Referenced by card (wiki-link backlinks)
+        if !backlinks.is_empty() {
+            content.push_str(r#"<div class="meta-card"><h2 class="section-title">Referenced by</h2>"#);
+            content.push_str(r#"<table class="mini-table"><tbody>"#);
+            for node in backlinks {
+                let href = html_escape(&format!("{}/", node.path.trim_end_matches('/')));
+                content.push_str(r#"<tr>"#);
+                content.push_str(&format!(
+                    r#"<td class="cell-value"><a class="link" href="{}" onclick="return tm.handleLinkClick(event, this)">{}</a></td>"#,
+                    href,
+                    html_escape(&node.name)
+                ));
+                content.push_str(r#"</tr>"#);
+            }
+            content.push_str(r#"</tbody></table></div>"#);
+        }
+
         content.push_str("</section>");
     }
 
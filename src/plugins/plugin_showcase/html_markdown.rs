@@ -1,7 +1,45 @@
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
 use std::fmt::Write as _;
+use std::ops::Range;
 
-/// Convert Markdown to HTML wrapped with classes for styling:
+use crate::plugins::plugin_showcase::manager_list::ProjectStructure;
+
+/// Knobs for `render_markdown`. The defaults reproduce the classic output.
+/// Deserialized from the `project_markdown` object in the project info JSON, e.g.
+/// `{ "heading_offset": 1, "smart_punctuation": true, "autolink": true }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    /// Added to every heading level (clamped to h6): with 1, `#` renders as <h2>.
+    pub heading_offset: u8,
+    /// Curly quotes, en/em dashes and ellipses.
+    pub smart_punctuation: bool,
+    /// Turn bare `http(s)://` and `www.` URLs in text into links.
+    pub autolink: bool,
+    /// Treat every newline inside a paragraph as a line break.
+    pub hard_wraps: bool,
+    /// Prefix for every generated class (`md` → `md-p`, `md-h1`, ...).
+    pub class_prefix: String,
+    /// Wrap standalone images in a link to the full-size file.
+    pub lightbox: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            heading_offset: 0,
+            smart_punctuation: false,
+            autolink: false,
+            hard_wraps: false,
+            class_prefix: "md".to_string(),
+            lightbox: false,
+        }
+    }
+}
+
+/// Convert Markdown to HTML wrapped with classes for styling
+/// (`md` is the default `class_prefix`):
 /// - Container: <div class="md"> ... </div>
 /// - Headings: <h1 class="md-h1">, ..., <h6 class="md-h6">
 /// - Paragraph: <p class="md-p">
@@ -9,159 +47,587 @@ use std::fmt::Write as _;
 /// - Code: <pre class="md-pre"><code class="md-code language-xxx">...</code></pre>
 /// - Inline code: <code class="md-code-inline">
 /// - Links: <a class="md-a" ...>
-/// - Images: <img class="md-img" ...>
+/// - Images: <img class="md-img" ...> (inline)
+/// - Figures: <figure class="md-figure"> with <figcaption class="md-figcaption"> from the image title
+///   (an image that is alone in its paragraph); `lightbox` wraps it in <a class="md-lightbox">
 /// - Blockquote: <blockquote class="md-blockquote">
+/// - Callouts: `> [!NOTE]`, `> [!TIP] Custom title`, ... → <aside class="md-callout md-callout-note">;
+///   `[!NOTE]-` / `[!NOTE]+` make a collapsed / expanded <details class="md-callout ...">
 /// - HR: <hr class="md-hr"/>
 /// - Tables (enabled): <table class="md-table"> ...
-pub fn render_markdown(md: &str) -> String {
+/// - Wiki links: `[[alchemists_convoy]]` / `[[alchemists_convoy#anchor|label]]` resolved against `projects`
+///   to <a class="md-a md-wikilink">; unresolved ones become <span class="md-wikilink md-wikilink-missing">
+pub fn render_markdown(md: &str, options: &RenderOptions, projects: &ProjectStructure) -> String {
+    let events = parse_events(md, options);
+
+    let mut r = Renderer {
+        opts: options,
+        projects,
+        prefix: attr_escape(&options.class_prefix),
+        out: String::with_capacity(md.len() + 256),
+        link_depth: 0,
+        in_code_block: false,
+        blockquote_ends: Vec::new(),
+    };
+    write!(r.out, r#"<div class="{}">"#, r.prefix).ok();
+
+    let mut i = 0;
+    while i < events.len() {
+        // A paragraph holding nothing but one image becomes a <figure>.
+        if let Some((img, para_end)) = standalone_image(&events, i) {
+            r.figure(&img);
+            i = para_end + 1;
+            continue;
+        }
+        if let Some(callout) = read_callout(&events, i) {
+            r.callout(&callout, &events[callout.title_events.clone()]);
+            i = callout.resume;
+            continue;
+        }
+        if let Some(img) = read_image(&events, i) {
+            r.img_tag(&img, true);
+            i = img.end + 1;
+            continue;
+        }
+
+        r.event(events[i].clone());
+        i += 1;
+    }
+
+    r.out.push_str("</div>");
+    r.out
+}
+
+/// Every `[[target]]` reference in `md` (outside code), in document order.
+pub fn wiki_link_targets(md: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    let mut in_code_block = false;
+    for ev in parse_events(md, &RenderOptions::default()) {
+        match ev {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(text) if !in_code_block => {
+                let mut rest: &str = &text;
+                while let Some((_, link, after)) = split_wiki_link(rest) {
+                    targets.push(link.target.to_string());
+                    rest = after;
+                }
+            }
+            _ => {}
+        }
+    }
+    targets
+}
+
+/// Number of words a reader sees: text and inline code, excluding markup and code blocks.
+/// Inline markup doesn't split words (`**bo**ld` is one); lone punctuation isn't a word.
+pub fn word_count(md: &str) -> usize {
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for ev in parse_events(md, &RenderOptions::default()) {
+        match ev {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(t) | Event::Code(t) if !in_code_block => text.push_str(&t),
+            // Inline markup joins text; blocks, breaks and raw HTML separate it
+            Event::Start(Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. })
+            | Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image) => {}
+            Event::Start(_) | Event::End(_) | Event::SoftBreak | Event::HardBreak => text.push(' '),
+            Event::Html(_) | Event::InlineHtml(_) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace()
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Parse with our extensions, merging runs of `Text` events: the parser splits text
+/// at every `[`, which would otherwise tear `[[wiki links]]` apart.
+fn parse_events<'a>(md: &'a str, options: &RenderOptions) -> Vec<Event<'a>> {
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_TABLES);
     opts.insert(Options::ENABLE_FOOTNOTES);
     opts.insert(Options::ENABLE_STRIKETHROUGH);
     opts.insert(Options::ENABLE_TASKLISTS);
+    if options.smart_punctuation {
+        opts.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
+
+    // Collected up front so images can look ahead for their alt text and paragraph bounds.
+    let mut events: Vec<Event> = Vec::new();
+    for ev in Parser::new_ext(md, opts) {
+        if let (Event::Text(next), Some(Event::Text(prev))) = (&ev, events.last_mut()) {
+            *prev = CowStr::from(format!("{prev}{next}"));
+            continue;
+        }
+        events.push(ev);
+    }
+    events
+}
+
+struct Renderer<'a> {
+    opts: &'a RenderOptions,
+    projects: &'a ProjectStructure,
+    /// `class_prefix`, already attribute-escaped.
+    prefix: String,
+    out: String,
+    /// Nesting of <a> elements; autolinking is skipped inside them.
+    link_depth: usize,
+    in_code_block: bool,
+    /// Closing markup for each open blockquote (plain quotes and callouts close differently).
+    blockquote_ends: Vec<&'static str>,
+}
+
+// --- callouts ---
+
+/// Known callout kinds: (marker, default title, icon).
+const CALLOUT_KINDS: &[(&str, &str, &str)] = &[
+    ("note", "Note", "ℹ️"),
+    ("tip", "Tip", "💡"),
+    ("important", "Important", "❗"),
+    ("warning", "Warning", "⚠️"),
+    ("caution", "Caution", "⛔"),
+];
+
+/// A blockquote opened with `[!KIND]`, optionally followed by `+`/`-` and a title.
+struct Callout {
+    kind: &'static str,
+    /// Title text on the marker line (the default title if the line has no title)
+    title: String,
+    /// Events after that text, up to the end of the marker line (inline title markup)
+    title_events: Range<usize>,
+    icon: &'static str,
+    /// None: always open; Some(open): collapsible, initially `open`.
+    fold: Option<bool>,
+    /// Whether the body starts mid-paragraph (text followed the marker line).
+    open_paragraph: bool,
+    /// Index of the first event after the marker line.
+    resume: usize,
+}
+
+/// `Start(BlockQuote), Start(Paragraph), Text("[!NOTE] Title")` → the callout header.
+fn read_callout(events: &[Event], i: usize) -> Option<Callout> {
+    if !matches!(events.get(i), Some(Event::Start(Tag::BlockQuote))) {
+        return None;
+    }
+    if !matches!(events.get(i + 1), Some(Event::Start(Tag::Paragraph))) {
+        return None;
+    }
+    let Some(Event::Text(first)) = events.get(i + 2) else {
+        return None;
+    };
+
+    let rest = first.trim_start().strip_prefix("[!")?;
+    let (marker, rest) = rest.split_once(']')?;
+    let &(kind, default_title, icon) = CALLOUT_KINDS
+        .iter()
+        .find(|(k, _, _)| k.eq_ignore_ascii_case(marker))?;
+
+    let (fold, rest) = match rest.chars().next() {
+        Some('-') => (Some(false), &rest[1..]),
+        Some('+') => (Some(true), &rest[1..]),
+        _ => (None, rest),
+    };
 
-    let parser = Parser::new_ext(md, opts);
+    // The title runs to the end of the marker line, markup included
+    let mut line_end = i + 3;
+    let mut depth = 0usize;
+    while let Some(ev) = events.get(line_end) {
+        match ev {
+            Event::Start(_) => depth += 1,
+            Event::End(_) if depth > 0 => depth -= 1,
+            Event::End(_) | Event::SoftBreak | Event::HardBreak if depth == 0 => break,
+            _ => {}
+        }
+        line_end += 1;
+    }
+    let title_events = i + 3..line_end;
+    let title = if title_events.is_empty() { rest.trim() } else { rest.trim_start() };
+    let title = if title.is_empty() && title_events.is_empty() { default_title } else { title };
+
+    // Marker line alone in its paragraph: drop the paragraph. Otherwise the body
+    // continues in the same paragraph after the line break.
+    let (open_paragraph, resume) = match events.get(line_end) {
+        Some(Event::End(TagEnd::Paragraph)) => (false, line_end + 1),
+        Some(Event::SoftBreak | Event::HardBreak) => (true, line_end + 1),
+        _ => (true, line_end),
+    };
+
+    Some(Callout {
+        kind,
+        title: title.to_string(),
+        title_events,
+        icon,
+        fold,
+        open_paragraph,
+        resume,
+    })
+}
 
-    let mut out = String::with_capacity(md.len() + 256);
-    out.push_str(r#"<div class="md">"#);
+// --- images ---
 
-    for ev in parser {
+/// An image with its alt text flattened to plain text.
+struct Image {
+    src: String,
+    title: String,
+    alt: String,
+    /// Index of the matching `End(Image)` event.
+    end: usize,
+}
+
+/// If `events[i]` starts an image, buffer it up to its matching end.
+/// Nested markup inside the alt text (emphasis, code, links, images) is reduced to its text.
+fn read_image(events: &[Event], i: usize) -> Option<Image> {
+    let Event::Start(Tag::Image { dest_url, title, .. }) = &events[i] else {
+        return None;
+    };
+
+    let mut alt = String::new();
+    let mut depth = 0usize;
+    for (j, ev) in events.iter().enumerate().skip(i + 1) {
         match ev {
-            Event::Start(tag) => start_tag(tag, &mut out),
-            Event::End(tag_end) => end_tag(tag_end, &mut out),
-            Event::Text(text) => {
-                escape_html(&mut out, &text);
+            Event::Start(Tag::Image { .. }) => depth += 1,
+            Event::End(TagEnd::Image) if depth == 0 => {
+                return Some(Image {
+                    src: dest_url.to_string(),
+                    title: title.to_string(),
+                    alt,
+                    end: j,
+                });
             }
+            Event::End(TagEnd::Image) => depth -= 1,
+            Event::Text(t) | Event::Code(t) => alt.push_str(t),
+            Event::SoftBreak | Event::HardBreak => alt.push(' '),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `Start(Paragraph), <image>, End(Paragraph)` → the image and the paragraph's end index.
+fn standalone_image(events: &[Event], i: usize) -> Option<(Image, usize)> {
+    if !matches!(events[i], Event::Start(Tag::Paragraph)) || i + 1 >= events.len() {
+        return None;
+    }
+    let img = read_image(events, i + 1)?;
+    match events.get(img.end + 1) {
+        Some(Event::End(TagEnd::Paragraph)) => {
+            let para_end = img.end + 1;
+            Some((img, para_end))
+        }
+        _ => None,
+    }
+}
+
+impl Renderer<'_> {
+    fn img_tag(&mut self, img: &Image, with_title: bool) {
+        write!(self.out, r#"<img class="{}-img" src=""#, self.prefix).ok();
+        attr_escape_to(&mut self.out, &img.src);
+        self.out.push_str(r#"" alt=""#);
+        attr_escape_to(&mut self.out, &img.alt);
+        self.out.push('"');
+        if with_title && !img.title.is_empty() {
+            self.out.push_str(r#" title=""#);
+            attr_escape_to(&mut self.out, &img.title);
+            self.out.push('"');
+        }
+        self.out.push_str(r#" loading="lazy" decoding="async"/>"#);
+    }
+
+    fn figure(&mut self, img: &Image) {
+        write!(self.out, r#"<figure class="{}-figure">"#, self.prefix).ok();
+        if self.opts.lightbox {
+            write!(self.out, r#"<a class="{}-lightbox" href=""#, self.prefix).ok();
+            attr_escape_to(&mut self.out, &img.src);
+            self.out.push_str(r#"" target="_blank" rel="noopener">"#);
+            self.img_tag(img, false);
+            self.out.push_str("</a>");
+        } else {
+            self.img_tag(img, false);
+        }
+        if !img.title.is_empty() {
+            write!(self.out, r#"<figcaption class="{}-figcaption">"#, self.prefix).ok();
+            escape_html(&mut self.out, &img.title);
+            self.out.push_str("</figcaption>");
+        }
+        self.out.push_str("</figure>");
+    }
+
+    /// Render one event that needs no look-ahead.
+    fn event(&mut self, ev: Event) {
+        match ev {
+            Event::Start(tag) => self.start_tag(tag),
+            Event::End(tag_end) => self.end_tag(tag_end),
+            Event::Text(text) => self.text(&text),
             Event::Code(text) => {
-                out.push_str(r#"<code class="md-code-inline">"#);
-                escape_html(&mut out, &text);
-                out.push_str("</code>");
+                write!(self.out, r#"<code class="{}-code-inline">"#, self.prefix).ok();
+                escape_html(&mut self.out, &text);
+                self.out.push_str("</code>");
             }
             Event::Html(html) | Event::InlineHtml(html) => {
                 // For safety, treat raw HTML as text. Change to push verbatim if you trust sources.
-                escape_html(&mut out, &html);
+                escape_html(&mut self.out, &html);
             }
             Event::FootnoteReference(name) => {
-                out.push_str(r#"<sup class="md-footnote-ref">"#);
-                escape_html(&mut out, &name);
-                out.push_str("</sup>");
+                write!(self.out, r#"<sup class="{}-footnote-ref">"#, self.prefix).ok();
+                escape_html(&mut self.out, &name);
+                self.out.push_str("</sup>");
+            }
+            Event::SoftBreak if self.opts.hard_wraps => {
+                write!(self.out, r#"<br class="{}-br"/>"#, self.prefix).ok();
+            }
+            Event::SoftBreak => self.out.push('\n'),
+            Event::HardBreak => {
+                write!(self.out, r#"<br class="{}-br"/>"#, self.prefix).ok();
+            }
+            Event::Rule => {
+                write!(self.out, r#"<hr class="{}-hr"/>"#, self.prefix).ok();
             }
-            Event::SoftBreak => out.push('\n'),
-            Event::HardBreak => out.push_str(r#"<br class="md-br"/>"#),
-            Event::Rule => out.push_str(r#"<hr class="md-hr"/>"#),
             Event::TaskListMarker(checked) => {
                 let attr = if checked { r#" checked="checked""# } else { "" };
-                out.push_str(r#"<input class="md-task" type="checkbox" disabled="disabled""#);
-                out.push_str(attr);
-                out.push_str(r#"/>"#);
-            }
-        }
-    }
-
-    out.push_str("</div>");
-    out
-}
-
-fn start_tag(tag: Tag, out: &mut String) {
-    match tag {
-        Tag::Paragraph => out.push_str(r#"<p class="md-p">"#),
-        Tag::Heading { level, .. } => {
-            let level_num = level as u8;
-            let cls = match level_num {
-                1 => "md-h1",
-                2 => "md-h2",
-                3 => "md-h3",
-                4 => "md-h4",
-                5 => "md-h5",
-                _ => "md-h6",
-            };
-            write!(out, r#"<h{} class="{}">"#, level_num, cls).ok();
-        }
-        Tag::BlockQuote => out.push_str(r#"<blockquote class="md-blockquote">"#),
-        Tag::CodeBlock(kind) => {
-            let lang = match kind {
-                CodeBlockKind::Indented => None,
-                CodeBlockKind::Fenced(lang) => {
-                    let l = lang.trim();
-                    if l.is_empty() { None } else { Some(l.to_owned()) }
+                write!(self.out, r#"<input class="{}-task" type="checkbox" disabled="disabled""#, self.prefix).ok();
+                self.out.push_str(attr);
+                self.out.push_str(r#"/>"#);
+            }
+        }
+    }
+
+    fn callout(&mut self, c: &Callout, title_events: &[Event]) {
+        let p = &self.prefix;
+        let out = &mut self.out;
+        let (title_tag, end) = match c.fold {
+            None => {
+                write!(out, r#"<aside class="{p}-callout {p}-callout-{}">"#, c.kind).ok();
+                ("div", "</div></aside>")
+            }
+            Some(open) => {
+                let open_attr = if open { " open" } else { "" };
+                write!(out, r#"<details class="{p}-callout {p}-callout-{}"{open_attr}>"#, c.kind).ok();
+                ("summary", "</div></details>")
+            }
+        };
+        write!(
+            out,
+            r#"<{title_tag} class="{p}-callout-title"><span class="{p}-callout-icon" aria-hidden="true">{}</span><span class="{p}-callout-label">"#,
+            c.icon
+        )
+        .ok();
+        escape_html(out, &c.title);
+        // Inline markup in the title (`[!NOTE] Use **this** one`)
+        for ev in title_events {
+            self.event(ev.clone());
+        }
+        let p = &self.prefix;
+        write!(self.out, r#"</span></{title_tag}><div class="{p}-callout-body">"#).ok();
+        if c.open_paragraph {
+            write!(self.out, r#"<p class="{p}-p">"#).ok();
+        }
+        self.blockquote_ends.push(end);
+    }
+
+    // --- text ---
+
+    fn text(&mut self, text: &str) {
+        if self.link_depth > 0 || self.in_code_block {
+            escape_html(&mut self.out, text);
+            return;
+        }
+
+        let mut rest = text;
+        while let Some((before, link, after)) = split_wiki_link(rest) {
+            self.plain_text(before);
+            self.wiki_link(&link);
+            rest = after;
+        }
+        self.plain_text(rest);
+    }
+
+    fn wiki_link(&mut self, link: &WikiLink) {
+        match self.projects.find_by_reference(link.target) {
+            Some(node) => {
+                let mut href = format!("{}/", node.path.trim_end_matches('/'));
+                if let Some(anchor) = link.anchor {
+                    href.push('#');
+                    href.push_str(anchor);
                 }
-            };
-            match lang {
-                Some(ref l) => write!(out, r#"<pre class="md-pre"><code class="md-code language-{}">"#, attr_escape(l)).ok(),
-                None => {
-                    out.push_str(r#"<pre class="md-pre"><code class="md-code">"#);
-                    Some(())
-                },
-            };
-        }
-        Tag::List(Some(_start)) => out.push_str(r#"<ol class="md-ol">"#),
-        Tag::List(None) => out.push_str(r#"<ul class="md-ul">"#),
-        Tag::Item => out.push_str(r#"<li class="md-li">"#),
-        Tag::Emphasis => out.push_str(r#"<em class="md-em">"#),
-        Tag::Strong => out.push_str(r#"<strong class="md-strong">"#),
-        Tag::Strikethrough => out.push_str(r#"<del class="md-del">"#),
-        Tag::Link { link_type: _lt, dest_url, title, id: _ } => {
-            // Add classes and safe target/rel for absolute URLs
-            let d = dest_url.to_string();
-            let (target, rel) = if is_abs_url(&d) {
-                (r#" target="_blank""#, r#" rel="noopener noreferrer""#)
-            } else {
-                ("", "")
-            };
-            out.push_str(r#"<a class="md-a" href=""#);
-            attr_escape_to(out, &d);
-            out.push('"');
-            if !title.is_empty() {
-                out.push_str(r#" title=""#);
-                attr_escape_to(out, &title);
-                out.push('"');
+                write!(self.out, r#"<a class="{p}-a {p}-wikilink" href=""#, p = self.prefix).ok();
+                attr_escape_to(&mut self.out, &href);
+                self.out.push_str(r#"" onclick="return tm.handleLinkClick(event, this)">"#);
+                escape_html(&mut self.out, link.label.unwrap_or(&node.name));
+                self.out.push_str("</a>");
+            }
+            None => {
+                // Reported once by `BacklinkIndex::build`, not on every render
+                write!(
+                    self.out,
+                    r#"<span class="{p}-wikilink {p}-wikilink-missing" title="Unresolved link: "#,
+                    p = self.prefix
+                )
+                .ok();
+                attr_escape_to(&mut self.out, link.target);
+                self.out.push_str(r#"">"#);
+                escape_html(&mut self.out, link.label.unwrap_or(link.target));
+                self.out.push_str("</span>");
+            }
+        }
+    }
+
+    fn plain_text(&mut self, text: &str) {
+        if !self.opts.autolink {
+            escape_html(&mut self.out, text);
+            return;
+        }
+
+        let mut rest = text;
+        while let Some((start, end)) = find_bare_url(rest) {
+            escape_html(&mut self.out, &rest[..start]);
+            let url = &rest[start..end];
+            let href = if url.starts_with("www.") { format!("https://{url}") } else { url.to_string() };
+            write!(self.out, r#"<a class="{}-a" href=""#, self.prefix).ok();
+            attr_escape_to(&mut self.out, &href);
+            self.out.push_str(r#"" target="_blank" rel="noopener noreferrer">"#);
+            escape_html(&mut self.out, url);
+            self.out.push_str("</a>");
+            rest = &rest[end..];
+        }
+        escape_html(&mut self.out, rest);
+    }
+
+    // --- tags ---
+
+    fn start_tag(&mut self, tag: Tag) {
+        let Renderer { opts, prefix: p, out, link_depth, in_code_block, blockquote_ends, .. } = self;
+        match tag {
+            Tag::Paragraph => write!(out, r#"<p class="{p}-p">"#).ok(),
+            Tag::Heading { level, .. } => {
+                let level_num = heading_level(level as u8, opts.heading_offset);
+                write!(out, r#"<h{level_num} class="{p}-h{level_num}">"#).ok()
             }
-            out.push_str(target);
-            out.push_str(rel);
-            out.push('>');
-        }
-        Tag::Image { link_type: _lt, title, dest_url, id: _ } => {
-            out.push_str(r#"<img class="md-img" src=""#);
-            attr_escape_to(out, &dest_url);
-            out.push('"');
-            if !title.is_empty() {
-                out.push_str(r#" title=""#);
-                attr_escape_to(out, &title);
+            Tag::BlockQuote => {
+                blockquote_ends.push("</blockquote>");
+                write!(out, r#"<blockquote class="{p}-blockquote">"#).ok()
+            }
+            Tag::CodeBlock(kind) => {
+                *in_code_block = true;
+                let lang = match kind {
+                    CodeBlockKind::Indented => None,
+                    CodeBlockKind::Fenced(lang) => {
+                        let l = lang.trim();
+                        if l.is_empty() { None } else { Some(l.to_owned()) }
+                    }
+                };
+                match lang {
+                    Some(ref l) => write!(out, r#"<pre class="{p}-pre"><code class="{p}-code language-{}">"#, attr_escape(l)).ok(),
+                    None => write!(out, r#"<pre class="{p}-pre"><code class="{p}-code">"#).ok(),
+                }
+            }
+            Tag::List(Some(_start)) => write!(out, r#"<ol class="{p}-ol">"#).ok(),
+            Tag::List(None) => write!(out, r#"<ul class="{p}-ul">"#).ok(),
+            Tag::Item => write!(out, r#"<li class="{p}-li">"#).ok(),
+            Tag::Emphasis => write!(out, r#"<em class="{p}-em">"#).ok(),
+            Tag::Strong => write!(out, r#"<strong class="{p}-strong">"#).ok(),
+            Tag::Strikethrough => write!(out, r#"<del class="{p}-del">"#).ok(),
+            Tag::Link { link_type: _lt, dest_url, title, id: _ } => {
+                *link_depth += 1;
+                // Add classes and safe target/rel for absolute URLs
+                let d = dest_url.to_string();
+                let (target, rel) = if is_abs_url(&d) {
+                    (r#" target="_blank""#, r#" rel="noopener noreferrer""#)
+                } else {
+                    ("", "")
+                };
+                write!(out, r#"<a class="{p}-a" href=""#).ok();
+                attr_escape_to(out, &d);
                 out.push('"');
+                if !title.is_empty() {
+                    out.push_str(r#" title=""#);
+                    attr_escape_to(out, &title);
+                    out.push('"');
+                }
+                out.push_str(target);
+                out.push_str(rel);
+                out.push('>');
+                Some(())
             }
-            out.push_str(r#" alt="""#); // actual alt text will come via Text before End(Image)
-        }
-        Tag::Table(_alignments) => out.push_str(r#"<table class="md-table">"#),
-        Tag::TableHead => out.push_str("<thead>"),
-        Tag::TableRow => out.push_str("<tr>"),
-        Tag::TableCell => out.push_str("<td>"),
-        _ => {}
-    }
-}
-
-fn end_tag(tag: pulldown_cmark::TagEnd, out: &mut String) {
-    use pulldown_cmark::TagEnd;
-    match tag {
-        TagEnd::Paragraph => out.push_str("</p>"),
-        TagEnd::Heading(level) => {
-            let _ = write!(out, "</h{}>", level as u8);
-        },
-        TagEnd::BlockQuote => out.push_str("</blockquote>"),
-        TagEnd::CodeBlock => out.push_str("</code></pre>"),
-        TagEnd::List(true) => out.push_str("</ol>"),
-        TagEnd::List(false) => out.push_str("</ul>"),
-        TagEnd::Item => out.push_str("</li>"),
-        TagEnd::Emphasis => out.push_str("</em>"),
-        TagEnd::Strong => out.push_str("</strong>"),
-        TagEnd::Strikethrough => out.push_str("</del>"),
-        TagEnd::Link => out.push_str("</a>"),
-        TagEnd::Image => out.push_str(r#""" />"#),
-        TagEnd::Table => out.push_str("</table>"),
-        TagEnd::TableHead => out.push_str("</thead>"),
-        TagEnd::TableRow => out.push_str("</tr>"),
-        TagEnd::TableCell => out.push_str("</td>"),
-        _ => {}
+            Tag::Table(_alignments) => write!(out, r#"<table class="{p}-table">"#).ok(),
+            Tag::TableHead => write!(out, "<thead>").ok(),
+            Tag::TableRow => write!(out, "<tr>").ok(),
+            Tag::TableCell => write!(out, "<td>").ok(),
+            _ => None,
+        };
     }
+
+    fn end_tag(&mut self, tag: TagEnd) {
+        let Renderer { opts, out, link_depth, in_code_block, blockquote_ends, .. } = self;
+        match tag {
+            TagEnd::Paragraph => out.push_str("</p>"),
+            TagEnd::Heading(level) => {
+                let level_num = heading_level(level as u8, opts.heading_offset);
+                let _ = write!(out, "</h{}>", level_num);
+            },
+            TagEnd::BlockQuote => out.push_str(blockquote_ends.pop().unwrap_or("</blockquote>")),
+            TagEnd::CodeBlock => {
+                *in_code_block = false;
+                out.push_str("</code></pre>");
+            }
+            TagEnd::List(true) => out.push_str("</ol>"),
+            TagEnd::List(false) => out.push_str("</ul>"),
+            TagEnd::Item => out.push_str("</li>"),
+            TagEnd::Emphasis => out.push_str("</em>"),
+            TagEnd::Strong => out.push_str("</strong>"),
+            TagEnd::Strikethrough => out.push_str("</del>"),
+            TagEnd::Link => {
+                *link_depth = link_depth.saturating_sub(1);
+                out.push_str("</a>");
+            }
+            TagEnd::Table => out.push_str("</table>"),
+            TagEnd::TableHead => out.push_str("</thead>"),
+            TagEnd::TableRow => out.push_str("</tr>"),
+            TagEnd::TableCell => out.push_str("</td>"),
+            _ => {}
+        }
+    }
+}
+
+/// `[[target]]`, `[[target|label]]` or `[[target#anchor|label]]`.
+struct WikiLink<'a> {
+    target: &'a str,
+    anchor: Option<&'a str>,
+    label: Option<&'a str>,
+}
+
+/// Split `s` around its first wiki link: (text before, link, text after).
+fn split_wiki_link(s: &str) -> Option<(&str, WikiLink<'_>, &str)> {
+    let mut from = 0;
+    while let Some(open) = s[from..].find("[[").map(|i| from + i) {
+        let inner_start = open + 2;
+        let close = s[inner_start..].find("]]").map(|i| inner_start + i)?;
+        let inner = &s[inner_start..close];
+        if inner.contains('[') || inner.contains('\n') {
+            from = inner_start;
+            continue;
+        }
+        let (target, label) = match inner.split_once('|') {
+            Some((t, l)) => (t.trim(), Some(l.trim()).filter(|l| !l.is_empty())),
+            None => (inner.trim(), None),
+        };
+        let (target, anchor) = match target.split_once('#') {
+            Some((t, a)) => (t.trim(), Some(a.trim()).filter(|a| !a.is_empty())),
+            None => (target, None),
+        };
+        if target.is_empty() {
+            from = close + 2;
+            continue;
+        }
+        return Some((&s[..open], WikiLink { target, anchor, label }, &s[close + 2..]));
+    }
+    None
+}
+
+/// Shift a heading level by `offset`, never going past h6.
+fn heading_level(level: u8, offset: u8) -> u8 {
+    level.saturating_add(offset).min(6)
 }
 
 fn is_abs_url(s: &str) -> bool {
@@ -169,6 +635,40 @@ fn is_abs_url(s: &str) -> bool {
     ss.starts_with("http://") || ss.starts_with("https://")
 }
 
+/// Byte range of the first bare URL (`http://`, `https://` or `www.`) in `s`.
+/// The URL runs to the next whitespace; trailing punctuation is left outside the link.
+fn find_bare_url(s: &str) -> Option<(usize, usize)> {
+    let lower = s.to_ascii_lowercase();
+    let start = ["https://", "http://", "www."]
+        .iter()
+        .filter_map(|pat| {
+            lower.match_indices(pat).map(|(i, _)| i).find(|&i| {
+                // Only at a word boundary, so "foowww.x" is left alone.
+                i == 0 || !s[..i].chars().next_back().is_some_and(|c| c.is_alphanumeric())
+            })
+        })
+        .min()?;
+
+    let tail = &s[start..];
+    let mut end = tail.find(char::is_whitespace).unwrap_or(tail.len());
+    while end > 0 && matches!(tail.as_bytes()[end - 1], b'.' | b',' | b':' | b';' | b'!' | b'?' | b')' | b'\'' | b'"') {
+        end -= 1;
+    }
+
+    let url = &tail[..end];
+    let scheme_len = if url.to_ascii_lowercase().starts_with("https://") {
+        8
+    } else if url.to_ascii_lowercase().starts_with("http://") {
+        7
+    } else {
+        4
+    };
+    if url.len() <= scheme_len {
+        return None;
+    }
+    Some((start, start + end))
+}
+
 // --- escaping helpers ---
 
 fn escape_html(out: &mut String, s: &str) {
@@ -202,3 +702,214 @@ fn attr_escape(s: &str) -> String {
     attr_escape_to(&mut buf, s);
     buf
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::plugin_showcase::manager_list::Node;
+
+    fn render(md: &str) -> String {
+        render_markdown(md, &RenderOptions::default(), &ProjectStructure::default())
+    }
+
+    fn callout_title(html: &str) -> &str {
+        let start = html.find(r#"<span class="md-callout-label">"#).expect("callout label") + 31;
+        let end = start + html[start..].find("</span></").expect("end of callout label");
+        &html[start..end]
+    }
+
+    #[test]
+    fn callout_plain_and_default_titles() {
+        assert_eq!(callout_title(&render("> [!TIP] Custom title\n> body")), "Custom title");
+        assert_eq!(callout_title(&render("> [!note]\n> body")), "Note");
+    }
+
+    #[test]
+    fn callout_title_keeps_inline_markup() {
+        let html = render("> [!note] Use **this** one\n> body text");
+        assert_eq!(callout_title(&html), r#"Use <strong class="md-strong">this</strong> one"#);
+        assert!(html.contains("body text"));
+        assert!(!html[..html.find("md-callout-body").unwrap()].contains("body text"));
+    }
+
+    #[test]
+    fn callout_title_markup_alone_in_paragraph() {
+        let html = render("> [!warning]- Check `config.json`\n\n> more");
+        assert!(callout_title(&html).contains("config.json</code>"), "{html}");
+        assert!(html.starts_with(r#"<div class="md"><details class="md-callout md-callout-warning">"#), "{html}");
+    }
+
+    #[test]
+    fn titled_image_becomes_captioned_figure() {
+        let html = render(r#"![A cat](cat.png "Our <cat>")"#);
+        assert_eq!(
+            html,
+            concat!(
+                r#"<div class="md"><figure class="md-figure">"#,
+                r#"<img class="md-img" src="cat.png" alt="A cat" loading="lazy" decoding="async"/>"#,
+                r#"<figcaption class="md-figcaption">Our &lt;cat&gt;</figcaption></figure></div>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn untitled_and_inline_images() {
+        let html = render("![x](a.png)");
+        assert!(html.contains("<figure"), "{html}");
+        assert!(!html.contains("<figcaption"), "{html}");
+
+        let html = render(r#"See ![x](a.png "t") here"#);
+        assert!(!html.contains("<figure"), "{html}");
+        assert!(html.contains(r#"alt="x" title="t""#), "{html}");
+    }
+
+    #[test]
+    fn image_alt_text_is_flattened_and_escaped() {
+        let html = render(r#"![a "quoted" *1 < 2* & `code` <b>x</b>](a.png)"#);
+        assert!(html.contains(r#"alt="a &quot;quoted&quot; 1 &lt; 2 &amp; code x""#), "{html}");
+        assert!(!html.contains("<b>") && !html.contains("<em"), "{html}");
+    }
+
+    #[test]
+    fn lightbox_wraps_figure_image() {
+        let opts = RenderOptions { lightbox: true, ..RenderOptions::default() };
+        let html = render_markdown("![x](big.png)", &opts, &ProjectStructure::default());
+        assert!(
+            html.contains(r#"<a class="md-lightbox" href="big.png" target="_blank" rel="noopener"><img "#),
+            "{html}"
+        );
+    }
+
+    fn render_with(md: &str, opts: RenderOptions) -> String {
+        render_markdown(md, &opts, &ProjectStructure::default())
+    }
+
+    #[test]
+    fn heading_offset_clamps_at_h6() {
+        let opts = RenderOptions { heading_offset: 2, ..RenderOptions::default() };
+        let html = render_with("# One\n\n##### Five", opts);
+        assert!(html.contains(r#"<h3 class="md-h3">One</h3>"#), "{html}");
+        assert!(html.contains(r#"<h6 class="md-h6">Five</h6>"#), "{html}");
+        assert_eq!(heading_level(1, 0), 1);
+        assert_eq!(heading_level(6, 1), 6);
+        assert_eq!(heading_level(3, u8::MAX), 6);
+    }
+
+    #[test]
+    fn find_bare_url_trims_trailing_punctuation() {
+        fn url(s: &str) -> Option<&str> {
+            find_bare_url(s).map(|(a, b)| &s[a..b])
+        }
+        assert_eq!(url("see https://example.com/a."), Some("https://example.com/a"));
+        assert_eq!(url("(at www.example.com), ok"), Some("www.example.com"));
+        assert_eq!(url("\"http://x.io/?q=1!\""), Some("http://x.io/?q=1"));
+        assert_eq!(url("foowww.example.com"), None);
+        assert_eq!(url("just https:// here"), None);
+        assert_eq!(url("no links"), None);
+    }
+
+    #[test]
+    fn autolink_links_bare_urls_only() {
+        let opts = || RenderOptions { autolink: true, ..RenderOptions::default() };
+        let html = render_with("Visit www.example.com.", opts());
+        assert!(
+            html.contains(r#"<a class="md-a" href="https://www.example.com" target="_blank" rel="noopener noreferrer">www.example.com</a>."#),
+            "{html}"
+        );
+
+        let html = render_with("[https://a.io](https://a.io) and <https://b.io>", opts());
+        // Already inside links: no nested <a>
+        assert_eq!(html.matches("<a ").count(), 2, "{html}");
+
+        let html = render("Visit www.example.com.");
+        assert!(!html.contains("<a "), "{html}");
+    }
+
+    #[test]
+    fn hard_wraps_turn_soft_breaks_into_br() {
+        let opts = RenderOptions { hard_wraps: true, ..RenderOptions::default() };
+        assert_eq!(render_with("one\ntwo", opts), r#"<div class="md"><p class="md-p">one<br class="md-br"/>two</p></div>"#);
+        assert_eq!(render("one\ntwo"), "<div class=\"md\"><p class=\"md-p\">one\ntwo</p></div>");
+    }
+
+    #[test]
+    fn class_prefix_applies_everywhere_and_is_escaped() {
+        let opts = RenderOptions { class_prefix: "doc".to_string(), ..RenderOptions::default() };
+        let html = render_with("# T\n\n*x* `y`", opts);
+        assert!(html.starts_with(r#"<div class="doc"><h1 class="doc-h1">"#), "{html}");
+        assert!(html.contains(r#"<em class="doc-em">x</em> <code class="doc-code-inline">y</code>"#), "{html}");
+        assert!(!html.contains("md-"), "{html}");
+
+        let opts = RenderOptions { class_prefix: r#"a"><b"#.to_string(), ..RenderOptions::default() };
+        let html = render_with("x", opts);
+        assert!(html.starts_with(r#"<div class="a&quot;&gt;&lt;b">"#), "{html}");
+    }
+
+    fn projects() -> ProjectStructure {
+        let node = |name: &str, path: &str, children| Node { name: name.into(), path: path.into(), children };
+        ProjectStructure::from_roots(vec![node(
+            "Game Design",
+            "/projects/game_design",
+            vec![node("Alchemist's Convoy", "/projects/game_design/alchemists_convoy", vec![])],
+        )])
+    }
+
+    fn render_linked(md: &str) -> String {
+        render_markdown(md, &RenderOptions::default(), &projects())
+    }
+
+    #[test]
+    fn split_wiki_link_parts() {
+        let (before, link, after) = split_wiki_link("see [[ a | b ]] now").unwrap();
+        assert_eq!((before, link.target, link.anchor, link.label, after), ("see ", "a", None, Some("b"), " now"));
+
+        let (_, link, _) = split_wiki_link("[[a#setup]]").unwrap();
+        assert_eq!((link.target, link.anchor, link.label), ("a", Some("setup"), None));
+
+        let (_, link, _) = split_wiki_link("[[a#|]]").unwrap();
+        assert_eq!((link.target, link.anchor, link.label), ("a", None, None));
+
+        assert!(split_wiki_link("unclosed [[a and more").is_none());
+        assert!(split_wiki_link("[[]] [[#only-anchor]] [[a\nb]]").is_none());
+        let (before, link, _) = split_wiki_link("[[x [[inner]]").unwrap();
+        assert_eq!((before, link.target), ("[[x ", "inner"));
+    }
+
+    #[test]
+    fn wiki_links_render_resolved_and_missing() {
+        let html = render_linked("[[alchemists_convoy|the convoy]] and [[Game Design#goals]]");
+        assert!(
+            html.contains(r#"<a class="md-a md-wikilink" href="/projects/game_design/alchemists_convoy/" onclick="return tm.handleLinkClick(event, this)">the convoy</a>"#),
+            "{html}"
+        );
+        assert!(html.contains(r#"href="/projects/game_design/#goals""#), "{html}");
+        assert!(html.contains(">Game Design</a>"), "{html}");
+
+        let html = render_linked("[[nowhere]]");
+        assert!(
+            html.contains(r#"<span class="md-wikilink md-wikilink-missing" title="Unresolved link: nowhere">nowhere</span>"#),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn wiki_links_left_alone_in_code_and_unclosed() {
+        let html = render_linked("`[[alchemists_convoy]]` and [[alchemists_convoy");
+        assert!(!html.contains("wikilink"), "{html}");
+        assert!(html.contains(r#"<code class="md-code-inline">[[alchemists_convoy]]</code> and [[alchemists_convoy"#), "{html}");
+
+        let md = "[[game_design]]\n\n```\n[[alchemists_convoy]]\n```\n\n`[[x]]` [[y#z|w]]";
+        assert_eq!(wiki_link_targets(md), ["game_design", "y"]);
+    }
+
+    #[test]
+    fn word_count_skips_code_blocks_and_markup() {
+        assert_eq!(word_count(""), 0);
+        assert_eq!(word_count("# Title\n\nOne **two** *three* `four`."), 5);
+        assert_eq!(word_count("**bo**ld and [li](x)nk"), 3);
+        assert_eq!(word_count("one\n\n```rust\nlet a = 1;\n```\n\n    indented code\n\ntwo"), 2);
+        assert_eq!(word_count("- a\n  - b\n\n| x | y |\n|---|---|\n| z | w |"), 6);
+        assert_eq!(word_count("wait — it's 2 - 3 & more..."), 5);
+        assert_eq!(word_count("line<br>break ![alt text](a.png)"), 4);
+    }
+}
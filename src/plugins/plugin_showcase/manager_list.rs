@@ -42,7 +42,7 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {}
 
 /// Public handle for read-only navigation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProjectStructure {
     roots: Vec<Node>,
 }
@@ -56,6 +56,12 @@ pub fn get_project_structure<P: AsRef<Path>>(path: P) -> Result<ProjectStructure
 }
 
 impl ProjectStructure {
+    /// Structure from in-memory nodes.
+    #[cfg(test)]
+    pub(crate) fn from_roots(roots: Vec<Node>) -> Self {
+        ProjectStructure { roots }
+    }
+
     /// Root nodes.
     pub fn roots(&self) -> &[Node] {
         &self.roots
@@ -76,13 +82,28 @@ impl ProjectStructure {
         None
     }
 
+    /// Find the node a wiki link such as `[[alchemists_convoy]]` refers to.
+    /// Matches the last path segment first, then the display name (both case-insensitive).
+    pub fn find_by_reference(&self, reference: &str) -> Option<&Node> {
+        let reference = reference.trim().trim_matches('/');
+        self.iter()
+            .find(|n| {
+                n.path
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|seg| seg.eq_ignore_ascii_case(reference))
+            })
+            .or_else(|| self.iter().find(|n| n.name.eq_ignore_ascii_case(reference)))
+    }
+
     /// Depth-first iterator over all nodes (read-only).
     pub fn iter(&self) -> impl Iterator<Item = &Node> {
         DfsIter::new(&self.roots)
     }
 }
 
-/// ---- internal helpers ----
+// ---- internal helpers ----
 
 fn count_nodes(n: &Node) -> usize {
     1 + n.children.iter().map(count_nodes).sum::<usize>()
@@ -123,3 +144,26 @@ impl<'a> Iterator for DfsIter<'a> {
         Some(node)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, path: &str, children: Vec<Node>) -> Node {
+        Node { name: name.into(), path: path.into(), children }
+    }
+
+    #[test]
+    fn find_by_reference_matches_slug_then_name() {
+        let s = ProjectStructure::from_roots(vec![
+            node("Development", "/projects/development", vec![node("Tools", "/projects/development/tools", vec![])]),
+            node("tools", "/projects/tools_archive", vec![]),
+        ]);
+        let path = |r: &str| s.find_by_reference(r).map(|n| n.path.as_str());
+        assert_eq!(path("tools"), Some("/projects/development/tools"));
+        assert_eq!(path(" /DEVELOPMENT/ "), Some("/projects/development"));
+        assert_eq!(path("Tools_Archive"), Some("/projects/tools_archive"));
+        assert_eq!(path("nowhere"), None);
+        assert_eq!(path(""), None);
+    }
+}
@@ -1,17 +1,26 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::plugins::plugin_showcase::html_markdown::{self, RenderOptions};
+use crate::plugins::plugin_showcase::manager_list::{Node, ProjectStructure};
+use crate::systems::sys_path::{self, PathError};
+
+/// Project info file names, in lookup order (see `get_project_info`).
+const INFO_FILES: [&str; 4] = ["projectData.json", "projectInfo.json", "project.json", "projectdata.json"];
 
 /// Link entry inside project info
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectLink {
     pub link: String,
     pub description: String,
 }
 
 /// Project info JSON structure
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectInfo {
     pub project_name: String,
     pub project_description: String,
@@ -27,13 +36,35 @@ pub struct ProjectInfo {
     pub project_content: String,
     #[serde(default)]
     pub project_links: Vec<ProjectLink>,
+    /// Markdown rendering options (heading offset, smart punctuation, ...)
+    #[serde(default)]
+    pub project_markdown: RenderOptions,
+    /// Explicit last-updated date (e.g. "2025-03-14"); falls back to the file modification time
+    #[serde(default, alias = "updated")]
+    pub project_updated: String,
+    /// Computed by `get_project_stats`, never read from JSON
+    #[serde(default, skip_deserializing)]
+    pub project_stats: ProjectStats,
 }
 
+/// Derived reading metadata for a project page
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ProjectStats {
+    pub word_count: usize,
+    pub reading_minutes: usize,
+    /// YYYY-MM-DD, if known
+    pub last_updated: Option<String>,
+}
+
+/// Average reading speed used for `reading_minutes`.
+const WORDS_PER_MINUTE: usize = 200;
+
 /// Simple native error type
 #[derive(Debug)]
 pub enum Error {
     Io(std::io::Error),
     Json(serde_json::Error),
+    Path(PathError),
 }
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self { Error::Io(e) }
@@ -41,11 +72,15 @@ impl From<std::io::Error> for Error {
 impl From<serde_json::Error> for Error {
     fn from(e: serde_json::Error) -> Self { Error::Json(e) }
 }
+impl From<PathError> for Error {
+    fn from(e: PathError) -> Self { Error::Path(e) }
+}
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::Io(e) => write!(f, "I/O error: {e}"),
             Error::Json(e) => write!(f, "JSON parse error: {e}"),
+            Error::Path(e) => write!(f, "Path error: {e}"),
         }
     }
 }
@@ -53,9 +88,28 @@ impl std::error::Error for Error {}
 
 /// Build absolute FS path to the project directory:
 /// data/projectData/<url_relative>/
-pub fn project_dir_for<P: AsRef<Path>>(base_data_dir: P, url_relative: &str) -> PathBuf {
-    let rel = url_relative.trim_start_matches('/');
-    base_data_dir.as_ref().join(rel)
+/// The directory must exist and stay inside `base_data_dir` (see `sys_path::resolve_path`).
+pub fn project_dir_for<P: AsRef<Path>>(base_data_dir: P, url_relative: &str) -> Result<PathBuf, Error> {
+    Ok(sys_path::resolve_path(base_data_dir.as_ref(), url_relative)?)
+}
+
+/// `name` inside the project directory, if it exists there.
+fn project_file(proj_dir: &Path, name: &str) -> Option<PathBuf> {
+    sys_path::resolve_path(proj_dir, name).ok().filter(|p| p.is_file())
+}
+
+/// Files a project's info and stats are read from: its folder (whose modification time changes
+/// when files are added or removed), the existing info files and the markdown.
+pub fn project_source_files<P: AsRef<Path>>(base_data_dir: P, url_relative: &str, info: &ProjectInfo) -> Vec<PathBuf> {
+    let Ok(proj_dir) = project_dir_for(base_data_dir, url_relative) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = INFO_FILES.iter().filter_map(|name| project_file(&proj_dir, name)).collect();
+    if let Some(p) = project_file(&proj_dir, &info.project_content) {
+        files.push(p);
+    }
+    files.insert(0, proj_dir);
+    files
 }
 
 /// Load project info from one of these in the project dir (in order):
@@ -64,12 +118,9 @@ pub fn project_dir_for<P: AsRef<Path>>(base_data_dir: P, url_relative: &str) ->
 /// - project.json
 /// - projectdata.json   (legacy)
 pub fn get_project_info<P: AsRef<Path>>(base_data_dir: P, url_relative: &str) -> Result<ProjectInfo, Error> {
-    let proj_dir = project_dir_for(base_data_dir, url_relative);
-    let candidates = ["projectData.json", "projectInfo.json", "project.json", "projectdata.json"];
-
-    for name in candidates {
-        let p = proj_dir.join(name);
-        if p.is_file() {
+    let proj_dir = project_dir_for(base_data_dir, url_relative)?;
+    for name in INFO_FILES {
+        if let Some(p) = project_file(&proj_dir, name) {
             let file = File::open(&p)?;
             let reader = BufReader::new(file);
             let info: ProjectInfo = serde_json::from_reader(reader)?;
@@ -94,8 +145,204 @@ pub fn load_markdown_content<P: AsRef<Path>>(
     if md_rel_path.trim().is_empty() {
         return Ok(String::new());
     }
-    let proj_dir = project_dir_for(base_data_dir, url_relative);
-    let path = proj_dir.join(md_rel_path);
+    // Relative to the project directory and confined to it
+    let proj_dir = project_dir_for(base_data_dir, url_relative)?;
+    let path = sys_path::resolve_path(&proj_dir, md_rel_path)?;
     let content = std::fs::read_to_string(path)?;
     Ok(content)
 }
+
+/// Word count and reading time from the markdown, last-updated from `project_updated`
+/// or else the newest modification time of the markdown / project info files.
+pub fn get_project_stats<P: AsRef<Path>>(
+    base_data_dir: P,
+    url_relative: &str,
+    info: &ProjectInfo,
+    md_text: &str,
+) -> ProjectStats {
+    let word_count = html_markdown::word_count(md_text);
+    let reading_minutes = if word_count == 0 {
+        0
+    } else {
+        word_count.div_ceil(WORDS_PER_MINUTE)
+    };
+
+    let last_updated = if !info.project_updated.trim().is_empty() {
+        Some(info.project_updated.trim().to_string())
+    } else if let Ok(proj_dir) = project_dir_for(base_data_dir, url_relative) {
+        let mut files: Vec<PathBuf> = INFO_FILES
+            .iter()
+            .filter_map(|name| project_file(&proj_dir, name))
+            .collect();
+        if !info.project_content.trim().is_empty()
+            && let Some(p) = project_file(&proj_dir, &info.project_content)
+        {
+            files.push(p);
+        }
+        files
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+            .max()
+            .map(format_date)
+    } else {
+        None
+    };
+
+    ProjectStats { word_count, reading_minutes, last_updated }
+}
+
+/// Format a timestamp as a UTC `YYYY-MM-DD` date.
+fn format_date(t: SystemTime) -> String {
+    let days = t.duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0) as i64;
+
+    // Civil-from-days (Howard Hinnant), valid for any date after 1970.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Wiki-link graph of all projects, built once and rebuilt when the project list, a project
+/// folder, its info file or its markdown changes (see `is_stale`). Unresolved links are
+/// reported while building, not on every page view.
+#[derive(Debug, Default)]
+pub struct BacklinkIndex {
+    /// Target project path -> paths of the projects linking to it (tree order)
+    backlinks: HashMap<String, Vec<String>>,
+    /// Files the index was built from, with their modification times
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl BacklinkIndex {
+    /// Read every project's markdown and collect its wiki links.
+    /// `list_path` is the project list `structure` was loaded from.
+    pub fn build<P: AsRef<Path>, Q: AsRef<Path>>(structure: &ProjectStructure, list_path: P, base_data_dir: Q) -> Self {
+        let base = base_data_dir.as_ref();
+        let mut index = BacklinkIndex::default();
+        index.stamp(list_path.as_ref().to_path_buf());
+
+        for node in structure.iter() {
+            let rel = node.path.strip_prefix("/projects").unwrap_or(&node.path);
+            let Ok(proj_dir) = project_dir_for(base, rel) else {
+                continue;
+            };
+            // The folder's own mtime changes when files are added or removed
+            index.stamp(proj_dir.clone());
+            for name in INFO_FILES {
+                if let Some(p) = project_file(&proj_dir, name) {
+                    index.stamp(p);
+                }
+            }
+            let Ok(info) = get_project_info(base, rel) else {
+                continue;
+            };
+            if let Some(p) = project_file(&proj_dir, &info.project_content) {
+                index.stamp(p);
+            }
+            let md = load_markdown_content(base, rel, &info.project_content).unwrap_or_default();
+            for target in html_markdown::wiki_link_targets(&md) {
+                let Some(hit) = structure.find_by_reference(&target) else {
+                    eprintln!("WARN: Unresolved wiki link [[{target}]] in {}", node.path);
+                    continue;
+                };
+                if hit.path == node.path {
+                    continue;
+                }
+                let sources = index.backlinks.entry(hit.path.clone()).or_default();
+                if !sources.contains(&node.path) {
+                    sources.push(node.path.clone());
+                }
+            }
+        }
+        index
+    }
+
+    /// True if any file the index was built from changed, appeared or disappeared.
+    pub fn is_stale(&self) -> bool {
+        self.stamps.iter().any(|(path, mtime)| modified(path) != *mtime)
+    }
+
+    /// Projects whose markdown wiki-links to `target` (tree order, `target` itself excluded).
+    pub fn backlinks<'a>(&self, structure: &'a ProjectStructure, target: &Node) -> Vec<&'a Node> {
+        let Some(sources) = self.backlinks.get(&target.path) else {
+            return Vec::new();
+        };
+        structure.iter().filter(|node| sources.contains(&node.path)).collect()
+    }
+
+    fn stamp(&mut self, path: PathBuf) {
+        let mtime = modified(&path);
+        self.stamps.push((path, mtime));
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn node(name: &str, path: &str, children: Vec<Node>) -> Node {
+        Node { name: name.into(), path: path.into(), children }
+    }
+
+    fn write_project(base: &Path, rel: &str, md: &str) -> PathBuf {
+        let dir = base.join(rel);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("projectData.json"), r#"{ "project_name": "x", "project_description": "",
+            "project_state": "", "project_content": "page.md" }"#)
+        .unwrap();
+        let md_path = dir.join("page.md");
+        std::fs::write(&md_path, md).unwrap();
+        md_path
+    }
+
+    #[test]
+    fn backlink_index_tracks_links_and_goes_stale() {
+        let base = std::env::temp_dir().join(format!("smn_manager_project_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let list = base.join("list.json");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::write(&list, "{}").unwrap();
+        write_project(&base, "game", "Parent page, links to [[game]] itself.");
+        let child_md = write_project(&base, "game/convoy", "Part of [[game|the games]] and [[missing]].");
+
+        let structure = ProjectStructure::from_roots(vec![node(
+            "Game",
+            "/projects/game",
+            vec![node("Convoy", "/projects/game/convoy", vec![])],
+        )]);
+        let game = &structure.roots()[0];
+        let convoy = &game.children[0];
+
+        let index = BacklinkIndex::build(&structure, &list, &base);
+        assert!(!index.is_stale());
+        let paths = |nodes: Vec<&Node>| nodes.iter().map(|n| n.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(index.backlinks(&structure, game)), ["/projects/game/convoy"]);
+        assert!(index.backlinks(&structure, convoy).is_empty());
+
+        // Drop the link; an explicit later mtime avoids depending on the filesystem's clock resolution
+        std::fs::write(&child_md, "No links any more.").unwrap();
+        let file = std::fs::File::options().write(true).open(&child_md).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        assert!(index.is_stale());
+
+        let index = BacklinkIndex::build(&structure, &list, &base);
+        assert!(!index.is_stale());
+        assert!(index.backlinks(&structure, game).is_empty());
+
+        std::fs::remove_file(&list).unwrap();
+        assert!(index.is_stale());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -4,28 +4,59 @@ use hyper::{
     header::HeaderValue,
 };
 use smn_web_core::structs::struct_plugin::Plugin;
-use std::{convert::Infallible};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, SystemTime},
+};
 
 mod html_builder;
 mod html_markdown;
 #[allow(unused)]
-mod manager_list;
+pub mod manager_list;
 #[allow(unused)]
-mod manager_project;
+pub mod manager_project;
+
+// Navigation tree of all projects.
+const PROJECT_LIST_PATH: &str = "data/displayProjectList.json";
+// Per-project folders (projectInfo.json + markdown), mirroring the /projects URL paths.
+const PROJECT_DATA_DIR: &str = "data/projectData";
+// How often the backlink index checks its files for changes.
+const BACKLINK_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
 
 // ---------------------- Plugin ----------------------
 
-pub struct PluginShowcase {}
+pub struct PluginShowcase {
+    /// Wiki-link backlinks of every project, shared with the refresh task.
+    backlinks: Arc<RwLock<manager_project::BacklinkIndex>>,
+}
 
 impl PluginShowcase {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            backlinks: Arc::new(RwLock::new(manager_project::BacklinkIndex::default())),
+        }
+    }
+
+    /// Read-only handle onto the showcase data, for other plugins (e.g. project components).
+    pub fn data(&self) -> ShowcaseData {
+        ShowcaseData::new(PROJECT_LIST_PATH, PROJECT_DATA_DIR)
+    }
+}
+
+impl Default for PluginShowcase {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl Plugin for PluginShowcase {
     async fn plugin_init(&mut self) {
+        *self.backlinks.write().expect("backlink index lock poisoned") = build_backlink_index();
+        tokio::spawn(refresh_backlink_index(self.backlinks.clone()));
         println!("{}", self.plugin_name());
     }
 
@@ -61,7 +92,7 @@ impl Plugin for PluginShowcase {
         // Get the project structure
         if rel_full.is_empty() {
             let project_structure =
-                match manager_list::get_project_structure("data/displayProjectList.json") {
+                match manager_list::get_project_structure(PROJECT_LIST_PATH) {
                     Ok(s) => s,
                     Err(e) => {
                         eprintln!("Failed to load project structure: {e}");
@@ -86,7 +117,7 @@ impl Plugin for PluginShowcase {
 
         // 2) Load structure to identify the project first.
         let project_structure =
-            match manager_list::get_project_structure("data/displayProjectList.json") {
+            match manager_list::get_project_structure(PROJECT_LIST_PATH) {
                 Ok(s) => s,
                 Err(e) => {
                     eprintln!("Failed to load project structure: {e}");
@@ -130,11 +161,11 @@ impl Plugin for PluginShowcase {
 
         // 4) Exact project hit → render project page (sidebar + content)
         let project_rel = strip_projects_prefix(project_abs_path).trim_start_matches('/'); // e.g. "game_design/alchemists_convoy"
-        match manager_project::get_project_info("data/projectData", project_rel) {
-            Ok(info) => {
+        match manager_project::get_project_info(PROJECT_DATA_DIR, project_rel) {
+            Ok(mut info) => {
                 // Markdown path is now NEXT TO the projectData.json (not inside "resources")
                 let md_text = match manager_project::load_markdown_content(
-                    "data/projectData",
+                    PROJECT_DATA_DIR,
                     project_rel,
                     &info.project_content,
                 ) {
@@ -150,12 +181,26 @@ impl Plugin for PluginShowcase {
                     }
                 };
 
+                info.project_stats = manager_project::get_project_stats(
+                    PROJECT_DATA_DIR,
+                    project_rel,
+                    &info,
+                    &md_text,
+                );
+
+                let backlinks = self
+                    .backlinks
+                    .read()
+                    .expect("backlink index lock poisoned")
+                    .backlinks(&project_structure, project_node);
+
                 let html = html_builder::generate_project_page_html(
                     &project_structure,
                     &path,
                     rel_full,
                     &info,
                     &md_text,
+                    &backlinks,
                 );
                 return Ok(Response::builder()
                     .status(StatusCode::OK)
@@ -177,8 +222,123 @@ impl Plugin for PluginShowcase {
     }
 }
 
+// ---------------------- Data access ----------------------
+
+/// Loads the project tree and project info the same way the showcase pages do.
+/// Parsed results are reused until their files change, so edits under `data/` still show up
+/// without a restart but a grid of cards doesn't re-read the list for every card.
+#[derive(Debug, Clone)]
+pub struct ShowcaseData {
+    list_path: PathBuf,
+    data_dir: PathBuf,
+    /// Shared by every clone (one per project component).
+    cache: Arc<Mutex<ShowcaseCache>>,
+}
+
+/// A parsed file set with the modification times it was read at.
+type Stamped<T> = (Vec<(PathBuf, Option<SystemTime>)>, Arc<T>);
+
+#[derive(Debug, Default)]
+struct ShowcaseCache {
+    structure: Option<Stamped<manager_list::ProjectStructure>>,
+    /// Node path -> project info with reading stats
+    projects: HashMap<String, Stamped<manager_project::ProjectInfo>>,
+}
+
+impl ShowcaseData {
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(list_path: P, data_dir: Q) -> Self {
+        Self {
+            list_path: list_path.as_ref().to_path_buf(),
+            data_dir: data_dir.as_ref().to_path_buf(),
+            cache: Arc::default(),
+        }
+    }
+
+    pub fn structure(&self) -> Result<Arc<manager_list::ProjectStructure>, manager_list::Error> {
+        let cached = self.lock_cache().structure.clone();
+        if let Some(hit) = fresh(cached) {
+            return Ok(hit);
+        }
+        let stamps = stamp_files(vec![self.list_path.clone()]);
+        let structure = Arc::new(manager_list::get_project_structure(&self.list_path)?);
+        self.lock_cache().structure = Some((stamps, structure.clone()));
+        Ok(structure)
+    }
+
+    /// Info of the project at `node` (with reading stats filled in).
+    pub fn project_info(
+        &self,
+        node: &manager_list::Node,
+    ) -> Result<Arc<manager_project::ProjectInfo>, manager_project::Error> {
+        let cached = self.lock_cache().projects.get(&node.path).cloned();
+        if let Some(hit) = fresh(cached) {
+            return Ok(hit);
+        }
+
+        let rel = strip_projects_prefix(&node.path).trim_start_matches('/');
+        let mut info = manager_project::get_project_info(&self.data_dir, rel)?;
+        let stamps = stamp_files(manager_project::project_source_files(&self.data_dir, rel, &info));
+        let md_text =
+            manager_project::load_markdown_content(&self.data_dir, rel, &info.project_content)
+                .unwrap_or_default();
+        info.project_stats = manager_project::get_project_stats(&self.data_dir, rel, &info, &md_text);
+        let info = Arc::new(info);
+        self.lock_cache().projects.insert(node.path.clone(), (stamps, info.clone()));
+        Ok(info)
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, ShowcaseCache> {
+        self.cache.lock().expect("showcase cache lock poisoned")
+    }
+}
+
+fn stamp_files(paths: Vec<PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+    paths
+        .into_iter()
+        .map(|p| {
+            let mtime = std::fs::metadata(&p).and_then(|m| m.modified()).ok();
+            (p, mtime)
+        })
+        .collect()
+}
+
+/// The cached value, if none of its files changed since it was read.
+fn fresh<T>(cached: Option<Stamped<T>>) -> Option<Arc<T>> {
+    let (stamps, value) = cached?;
+    let unchanged = !stamps.is_empty()
+        && stamps.iter().all(|(path, mtime)| {
+            mtime.is_some() && std::fs::metadata(path).and_then(|m| m.modified()).ok() == *mtime
+        });
+    unchanged.then_some(value)
+}
+
 // ========== UTILITIES  ==========
 
+/// Index the wiki links of every project. Without a readable project list the index is
+/// empty and gets rebuilt once the list changes.
+fn build_backlink_index() -> manager_project::BacklinkIndex {
+    let structure = manager_list::get_project_structure(PROJECT_LIST_PATH).unwrap_or_else(|e| {
+        eprintln!("WARN: Failed to index backlinks, project list unavailable: {e}");
+        manager_list::ProjectStructure::default()
+    });
+    manager_project::BacklinkIndex::build(&structure, PROJECT_LIST_PATH, PROJECT_DATA_DIR)
+}
+
+/// Rebuild the backlink index whenever one of its files changes.
+async fn refresh_backlink_index(index: Arc<RwLock<manager_project::BacklinkIndex>>) {
+    let mut interval = tokio::time::interval(BACKLINK_REFRESH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let stale = index.read().expect("backlink index lock poisoned").is_stale();
+        if !stale {
+            continue;
+        }
+        if let Ok(rebuilt) = tokio::task::spawn_blocking(build_backlink_index).await {
+            *index.write().expect("backlink index lock poisoned") = rebuilt;
+        }
+    }
+}
+
 fn find_longest_matching_project<'a>(
     structure: &'a manager_list::ProjectStructure,
     req_path: &str,
@@ -188,15 +348,14 @@ fn find_longest_matching_project<'a>(
 
     for node in structure.iter() {
         let p = node.path.as_str();
-        if req_path == p
+        if (req_path == p
             || (req_path.len() > p.len()
                 && req_path.starts_with(p)
-                && req_path.as_bytes().get(p.len()) == Some(&b'/'))
+                && req_path.as_bytes().get(p.len()) == Some(&b'/')))
+            && p.len() > best_len
         {
-            if p.len() > best_len {
-                best_len = p.len();
-                best = Some(node);
-            }
+            best_len = p.len();
+            best = Some(node);
         }
     }
     best
@@ -205,3 +364,49 @@ fn find_longest_matching_project<'a>(
 fn strip_projects_prefix(p: &str) -> &str {
     p.strip_prefix("/projects").unwrap_or(p)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch_later(path: &Path) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+    }
+
+    #[test]
+    fn showcase_data_reuses_parsed_files_until_they_change() {
+        let base = std::env::temp_dir().join(format!("smn_plugin_showcase_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let proj_dir = base.join("data").join("game");
+        std::fs::create_dir_all(&proj_dir).unwrap();
+        let list = base.join("list.json");
+        std::fs::write(&list, r#"{ "project_tree": [ { "name": "Game", "path": "/projects/game" } ] }"#).unwrap();
+        std::fs::write(proj_dir.join("projectData.json"), r#"{ "project_name": "Game", "project_description": "",
+            "project_state": "", "project_content": "page.md" }"#)
+        .unwrap();
+        let md = proj_dir.join("page.md");
+        std::fs::write(&md, "one two three").unwrap();
+
+        let data = ShowcaseData::new(&list, base.join("data"));
+        let structure = data.structure().unwrap();
+        assert!(Arc::ptr_eq(&structure, &data.clone().structure().unwrap()));
+        let node = &structure.roots()[0];
+        let info = data.project_info(node).unwrap();
+        assert_eq!(info.project_stats.word_count, 3);
+        assert!(Arc::ptr_eq(&info, &data.project_info(node).unwrap()));
+
+        std::fs::write(&md, "one two three four").unwrap();
+        touch_later(&md);
+        let info = data.project_info(node).unwrap();
+        assert_eq!(info.project_stats.word_count, 4);
+
+        std::fs::write(&list, r#"{ "project_tree": [] }"#).unwrap();
+        touch_later(&list);
+        assert_eq!(data.structure().unwrap().count(), 0);
+
+        std::fs::remove_file(&list).unwrap();
+        assert!(data.structure().is_err());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
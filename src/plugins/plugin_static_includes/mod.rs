@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use hyper::{
+    Body, Request, Response, StatusCode,
+    body::to_bytes,
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+};
+use smn_web_core::{
+    plugins::plugin_static::PluginStatic,
+    structs::struct_plugin::{Plugin, PluginContext},
+};
+use std::{convert::Infallible, path::Path};
+
+use crate::plugins::plugin_components::component_include::ComponentIncludes;
+use crate::systems::{sys_path::{self, PathError}, sys_static};
+
+// Where `PluginStatic` serves files from.
+const STATIC_DIR: &str = "./static";
+
+// ---------------------- Plugin ----------------------
+
+/// Wraps `PluginStatic` and expands `<smn-component>` markers in the HTML pages it serves,
+/// so component content is in the page for visitors without JS and for crawlers.
+/// Other files with an explicit extension (videos, PDFs, …) are streamed with Range support,
+/// and every path is checked by `sys_path` first.
+pub struct PluginStaticIncludes {
+    inner: PluginStatic,
+    includes: ComponentIncludes,
+}
+
+impl PluginStaticIncludes {
+    /// `includes` comes from `PluginComponents::includes()`.
+    pub fn new(inner: PluginStatic, includes: ComponentIncludes) -> Self {
+        Self { inner, includes }
+    }
+}
+
+#[async_trait]
+impl Plugin for PluginStaticIncludes {
+    async fn plugin_init(&mut self) {
+        self.inner.plugin_init().await;
+        println!("{} initialized", self.plugin_name());
+    }
+
+    fn plugin_name(&self) -> &str {
+        "PluginStaticIncludes"
+    }
+
+    fn plugin_can_handle(&self, req: &Request<Body>) -> bool {
+        self.inner.plugin_can_handle(req)
+    }
+
+    async fn plugin_handle(
+        &self,
+        req: Request<Body>,
+        ctx: &PluginContext,
+    ) -> Result<Response<Body>, Infallible> {
+        if let Some(resp) = serve_asset(&req).await {
+            return Ok(resp);
+        }
+
+        // Built before `req` is handed on; includes render for this page's request.
+        let component_ctx = self.includes.context(&req, ctx);
+        let mut resp = self.inner.plugin_handle(req, ctx).await?;
+        sys_static::set_nosniff(resp.headers_mut());
+
+        let is_html = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/html"));
+        if !resp.status().is_success() || !is_html {
+            return Ok(resp);
+        }
+
+        // Static HTML pages are small; buffer them to look for markers.
+        let (mut parts, body) = resp.into_parts();
+        let bytes = match to_bytes(body).await {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("WARN: Failed to buffer static page for includes: {e}");
+                return Ok(Response::from_parts(parts, Body::empty()));
+            }
+        };
+
+        let html = String::from_utf8_lossy(&bytes);
+        if !ComponentIncludes::has_markers(&html) {
+            return Ok(Response::from_parts(parts, Body::from(bytes)));
+        }
+
+        let expanded = self.includes.expand(&component_ctx, &html).await;
+        parts.headers.remove(CONTENT_LENGTH);
+        Ok(Response::from_parts(parts, Body::from(expanded)))
+    }
+}
+
+/// Requests `PluginStatic` must not see, or a non-HTML file to stream.
+/// Paths are decoded and confined to `./static` by `sys_path`: traversal attempts are refused,
+/// hidden and backup files are 404. Files with an extension other than `.html` are streamed
+/// with Range support; everything else (pages, extensionless routes) is left to `PluginStatic`.
+async fn serve_asset(req: &Request<Body>) -> Option<Response<Body>> {
+    let raw = req.uri().path().trim_start_matches('/');
+    let path = match sys_path::resolve_url_path(Path::new(STATIC_DIR), raw) {
+        Ok(p) => p,
+        // e.g. "/game-design" → game-design.html, resolved by `PluginStatic`
+        Err(PathError::NotFound) => return None,
+        Err(e) if e.is_not_found() => return Some(status_response(StatusCode::NOT_FOUND, "404 Not Found")),
+        Err(PathError::Io(e)) => {
+            eprintln!("WARN: Failed to resolve static path '{raw}': {e}");
+            return None;
+        }
+        Err(_) => return Some(status_response(StatusCode::FORBIDDEN, "403 Forbidden: invalid path")),
+    };
+    let ext = path.extension().and_then(|e| e.to_str())?.to_ascii_lowercase();
+    if ext == "html" || ext == "htm" || path.is_dir() {
+        return None;
+    }
+    sys_static::serve_file(req.method(), req.headers(), &path).await
+}
+
+fn status_response(code: StatusCode, msg: &'static str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(msg));
+    *resp.status_mut() = code;
+    resp
+}
@@ -0,0 +1,3 @@
+pub mod sys_mime;
+pub mod sys_path;
+pub mod sys_static;
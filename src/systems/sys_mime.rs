@@ -0,0 +1,349 @@
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+/// Built-in extension → MIME type table (lowercase extensions, no parameters).
+/// Text types get `; charset=utf-8` appended by `MimeRegistry::content_type`.
+const BUILTIN_TYPES: &[(&str, &str)] = &[
+    // Documents and text
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("xhtml", "application/xhtml+xml"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("mjs", "text/javascript"),
+    ("cjs", "text/javascript"),
+    ("map", "application/json"),
+    ("json", "application/json"),
+    ("jsonld", "application/ld+json"),
+    ("webmanifest", "application/manifest+json"),
+    ("xml", "application/xml"),
+    ("rss", "application/rss+xml"),
+    ("atom", "application/atom+xml"),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("tsv", "text/tab-separated-values"),
+    ("ics", "text/calendar"),
+    ("vtt", "text/vtt"),
+    ("srt", "application/x-subrip"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("toml", "application/toml"),
+    ("pdf", "application/pdf"),
+    ("rtf", "application/rtf"),
+    ("epub", "application/epub+zip"),
+    ("doc", "application/msword"),
+    ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+    ("xls", "application/vnd.ms-excel"),
+    ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+    ("ppt", "application/vnd.ms-powerpoint"),
+    ("pptx", "application/vnd.openxmlformats-officedocument.presentationml.presentation"),
+    ("odt", "application/vnd.oasis.opendocument.text"),
+    // Images
+    ("png", "image/png"),
+    ("apng", "image/apng"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("jfif", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("heic", "image/heic"),
+    ("heif", "image/heif"),
+    ("jxl", "image/jxl"),
+    ("svg", "image/svg+xml"),
+    // Gzipped SVG: served with `Content-Encoding: gzip` (see `content_encoding`)
+    ("svgz", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+    ("cur", "image/x-icon"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("psd", "image/vnd.adobe.photoshop"),
+    ("exr", "image/x-exr"),
+    ("tga", "image/x-tga"),
+    ("dds", "image/vnd-ms.dds"),
+    ("ktx", "image/ktx"),
+    ("ktx2", "image/ktx2"),
+    // Audio
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("aac", "audio/aac"),
+    ("wav", "audio/wav"),
+    ("ogg", "audio/ogg"),
+    ("oga", "audio/ogg"),
+    ("opus", "audio/ogg"),
+    ("flac", "audio/flac"),
+    ("weba", "audio/webm"),
+    ("mid", "audio/midi"),
+    ("midi", "audio/midi"),
+    // Video
+    ("mp4", "video/mp4"),
+    ("m4v", "video/mp4"),
+    ("webm", "video/webm"),
+    ("ogv", "video/ogg"),
+    ("mov", "video/quicktime"),
+    ("mkv", "video/x-matroska"),
+    ("avi", "video/x-msvideo"),
+    ("mpeg", "video/mpeg"),
+    ("mpg", "video/mpeg"),
+    ("ts", "video/mp2t"),
+    ("m3u8", "application/vnd.apple.mpegurl"),
+    ("mpd", "application/dash+xml"),
+    // Fonts
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("eot", "application/vnd.ms-fontobject"),
+    // 3D
+    ("glb", "model/gltf-binary"),
+    ("gltf", "model/gltf+json"),
+    ("obj", "model/obj"),
+    ("mtl", "model/mtl"),
+    ("stl", "model/stl"),
+    ("fbx", "application/octet-stream"),
+    ("usdz", "model/vnd.usdz+zip"),
+    ("ply", "application/octet-stream"),
+    // Archives and binaries
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    ("tgz", "application/gzip"),
+    ("tar", "application/x-tar"),
+    ("7z", "application/x-7z-compressed"),
+    ("rar", "application/vnd.rar"),
+    ("bz2", "application/x-bzip2"),
+    ("xz", "application/x-xz"),
+    ("wasm", "application/wasm"),
+    ("bin", "application/octet-stream"),
+    ("exe", "application/octet-stream"),
+    ("apk", "application/vnd.android.package-archive"),
+    ("dmg", "application/x-apple-diskimage"),
+];
+
+/// For unknown extensions.
+pub const DEFAULT_TYPE: &str = "application/octet-stream";
+
+/// Extension → MIME type lookup: the built-in table plus configured overrides.
+#[derive(Debug, Clone)]
+pub struct MimeRegistry {
+    types: HashMap<String, String>,
+}
+
+/// `mime_types.json`: `{ "types": { "ktx2": "image/ktx2", ".hdr": "image/vnd.radiance" } }`
+#[derive(Debug, Deserialize)]
+pub struct MimeConfig {
+    #[serde(default)]
+    pub types: HashMap<String, String>,
+}
+
+impl Default for MimeRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl MimeRegistry {
+    pub fn builtin() -> Self {
+        let types = BUILTIN_TYPES
+            .iter()
+            .map(|(ext, mime)| (ext.to_string(), mime.to_string()))
+            .collect();
+        Self { types }
+    }
+
+    /// Add or replace types; extensions are matched case-insensitively, a leading dot is optional.
+    pub fn with_overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        for (ext, mime) in overrides {
+            let ext = ext.trim().trim_start_matches('.').to_ascii_lowercase();
+            let mime = mime.trim().to_string();
+            if ext.is_empty() || mime.is_empty() {
+                eprintln!("WARN: Ignoring empty MIME override '{ext}' -> '{mime}'");
+                continue;
+            }
+            self.types.insert(ext, mime);
+        }
+        self
+    }
+
+    /// Built-in table plus the overrides in `path`. A missing file means no overrides.
+    pub fn from_config<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Ok(Self::builtin());
+        }
+        let text = std::fs::read_to_string(path)?;
+        let config: MimeConfig = serde_json::from_str(&text)?;
+        Ok(Self::builtin().with_overrides(config.types))
+    }
+
+    /// Bare MIME type for an extension, if known.
+    pub fn lookup(&self, ext: &str) -> Option<&str> {
+        self.types.get(&ext.to_ascii_lowercase()).map(String::as_str)
+    }
+
+    /// `Content-Type` value for a file: its type (`DEFAULT_TYPE` if unknown), with
+    /// `; charset=utf-8` for text types that don't already carry parameters.
+    pub fn content_type(&self, path: &Path) -> String {
+        let mime = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.lookup(ext))
+            .unwrap_or(DEFAULT_TYPE);
+        if is_text(mime) && !mime.contains(';') {
+            format!("{mime}; charset=utf-8")
+        } else {
+            mime.to_string()
+        }
+    }
+}
+
+/// Types served as UTF-8 text.
+fn is_text(mime: &str) -> bool {
+    let essence = mime.split(';').next().unwrap_or(mime).trim();
+    essence.starts_with("text/")
+        || matches!(
+            essence,
+            "application/javascript"
+                | "application/json"
+                | "application/xml"
+                | "application/yaml"
+                | "application/toml"
+                | "application/x-subrip"
+                | "application/xhtml+xml"
+                | "image/svg+xml"
+        )
+        || essence.ends_with("+json")
+        || (essence.ends_with("+xml") && essence.starts_with("application/"))
+}
+
+/// Extensions of files stored compressed, with the `Content-Encoding` they must be sent with
+/// so browsers decode them before interpreting the declared type.
+const ENCODED_TYPES: &[(&str, &str)] = &[("svgz", "gzip")];
+
+/// `Content-Encoding` for a file stored compressed (e.g. `.svgz`), if any.
+pub fn content_encoding(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?;
+    ENCODED_TYPES
+        .iter()
+        .find(|(e, _)| e.eq_ignore_ascii_case(ext))
+        .map(|(_, encoding)| *encoding)
+}
+
+static REGISTRY: OnceLock<MimeRegistry> = OnceLock::new();
+
+/// Install the registry every plugin uses. Call once at startup, before serving;
+/// later calls are ignored (with a warning).
+pub fn init(registry: MimeRegistry) {
+    if REGISTRY.set(registry).is_err() {
+        eprintln!("WARN: MIME registry already initialized; ignoring new configuration");
+    }
+}
+
+/// The shared registry (built-in types only if `init` was never called).
+pub fn registry() -> &'static MimeRegistry {
+    REGISTRY.get_or_init(MimeRegistry::builtin)
+}
+
+/// `Content-Type` for `path` from the shared registry.
+pub fn content_type(path: &Path) -> String {
+    registry().content_type(path)
+}
+
+/// Simple native error type
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self { Error::Io(e) }
+}
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self { Error::Json(e) }
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Json(e) => write!(f, "JSON parse error: {e}"),
+        }
+    }
+}
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_ignores_case() {
+        let registry = MimeRegistry::builtin();
+        assert_eq!(registry.lookup("png"), Some("image/png"));
+        assert_eq!(registry.lookup("PNG"), Some("image/png"));
+        assert_eq!(registry.content_type(Path::new("photo.JPG")), "image/jpeg");
+        assert_eq!(registry.content_type(Path::new("README.Md")), registry.content_type(Path::new("readme.md")));
+        assert_eq!(registry.lookup("nope"), None);
+    }
+
+    #[test]
+    fn charset_only_on_text_types() {
+        let registry = MimeRegistry::builtin();
+        let ct = |p: &str| registry.content_type(Path::new(p));
+        assert_eq!(ct("index.html"), "text/html; charset=utf-8");
+        assert_eq!(ct("app.js"), "text/javascript; charset=utf-8");
+        assert_eq!(ct("data.json"), "application/json; charset=utf-8");
+        assert_eq!(ct("icon.svg"), "image/svg+xml; charset=utf-8");
+        assert_eq!(ct("photo.png"), "image/png");
+        assert_eq!(ct("font.woff2"), "font/woff2");
+        assert_eq!(ct("module.wasm"), "application/wasm");
+        assert_eq!(ct("archive.unknownext"), DEFAULT_TYPE);
+        assert_eq!(ct("no_extension"), DEFAULT_TYPE);
+    }
+
+    #[test]
+    fn overrides_add_and_replace_types() {
+        let overrides = HashMap::from([
+            (".KTX2".to_string(), "image/ktx2".to_string()),
+            ("hdr".to_string(), "image/vnd.radiance".to_string()),
+            ("png".to_string(), "image/x-custom".to_string()),
+            ("".to_string(), "ignored/empty".to_string()),
+            ("txt".to_string(), "text/plain; charset=latin1".to_string()),
+        ]);
+        let registry = MimeRegistry::builtin().with_overrides(overrides);
+        assert_eq!(registry.content_type(Path::new("tex.ktx2")), "image/ktx2");
+        assert_eq!(registry.content_type(Path::new("sky.HDR")), "image/vnd.radiance");
+        assert_eq!(registry.lookup("png"), Some("image/x-custom"));
+        assert_eq!(registry.lookup(""), None);
+        // Explicit parameters are kept as configured
+        assert_eq!(registry.content_type(Path::new("a.txt")), "text/plain; charset=latin1");
+    }
+
+    #[test]
+    fn loads_overrides_from_config() {
+        let dir = std::env::temp_dir().join(format!("smn_sys_mime_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("mime_types.json");
+        std::fs::write(&config, r#"{ "types": { "ktx2": "image/ktx2", "hdr": "image/vnd.radiance" } }"#).unwrap();
+
+        let registry = MimeRegistry::from_config(&config).unwrap();
+        assert_eq!(registry.lookup("ktx2"), Some("image/ktx2"));
+        assert_eq!(registry.lookup("hdr"), Some("image/vnd.radiance"));
+        assert_eq!(registry.lookup("css"), Some("text/css"));
+
+        // Missing file: built-ins only; broken file: an error
+        let missing = MimeRegistry::from_config(dir.join("missing.json")).unwrap();
+        assert_eq!(missing.lookup("ktx2"), MimeRegistry::builtin().lookup("ktx2"));
+        std::fs::write(&config, "{ not json").unwrap();
+        assert!(matches!(MimeRegistry::from_config(&config), Err(Error::Json(_))));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn svgz_is_gzip_encoded_svg() {
+        assert_eq!(content_encoding(Path::new("logo.svgz")), Some("gzip"));
+        assert_eq!(content_encoding(Path::new("logo.SVGZ")), Some("gzip"));
+        assert_eq!(content_encoding(Path::new("logo.svg")), None);
+        assert!(MimeRegistry::builtin().content_type(Path::new("logo.svgz")).starts_with("image/svg+xml"));
+    }
+}
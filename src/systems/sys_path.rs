@@ -0,0 +1,251 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Why a requested path was refused.
+#[derive(Debug)]
+pub enum PathError {
+    /// Invalid percent-encoding, non-UTF-8 bytes, control characters or double encoding
+    Encoding,
+    /// `..` segments, backslashes or drive prefixes
+    Traversal,
+    /// A segment starting with `.` (dotfiles, `.git/`, …)
+    Hidden,
+    /// Editor / backup leftovers such as `page.html~`, `page.bak`, `#page#`, `.swp`
+    Backup,
+    /// Resolves outside the root (through a symlink)
+    Outside,
+    NotFound,
+    Io(io::Error),
+}
+
+impl PathError {
+    /// Whether the request should look like a plain 404 to the client
+    /// (as opposed to a rejected, malicious-looking path).
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, PathError::NotFound | PathError::Hidden | PathError::Backup)
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Encoding => write!(f, "invalid encoding in path"),
+            PathError::Traversal => write!(f, "path traversal rejected"),
+            PathError::Hidden => write!(f, "hidden files are not served"),
+            PathError::Backup => write!(f, "backup files are not served"),
+            PathError::Outside => write!(f, "path resolves outside its root"),
+            PathError::NotFound => write!(f, "not found"),
+            PathError::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+impl std::error::Error for PathError {}
+
+// Extensions of editor swap files and backups.
+const BACKUP_EXTENSIONS: &[&str] = &["bak", "backup", "old", "orig", "save", "swo", "swp", "tmp", "rej"];
+
+/// Resolve a raw URL path (still percent-encoded, e.g. from `req.uri().path()`) under `root`.
+/// See `resolve_path` for the checks; decoding happens exactly once, so double-encoded
+/// payloads (`%252e`) are rejected rather than decoded again.
+pub fn resolve_url_path(root: &Path, raw: &str) -> Result<PathBuf, PathError> {
+    let decoded = urlencoding::decode(raw).map_err(|_| PathError::Encoding)?;
+    if has_percent_escape(&decoded) {
+        return Err(PathError::Encoding);
+    }
+    resolve_path(root, &decoded)
+}
+
+/// Resolve an already decoded relative path under `root`:
+/// normalize (`/` separated, empty and `.` segments dropped), reject `..`, backslashes,
+/// hidden and backup names, then canonicalize and check that the file (after following
+/// symlinks) is still inside the canonical root. Returns the canonical path of an existing entry.
+pub fn resolve_path(root: &Path, rel: &str) -> Result<PathBuf, PathError> {
+    let segments = normalize(rel)?;
+    let root = root.canonicalize().map_err(io_error)?;
+    let joined = segments.iter().fold(root.clone(), |p, s| p.join(s));
+    let resolved = joined.canonicalize().map_err(io_error)?;
+    if !resolved.starts_with(&root) {
+        return Err(PathError::Outside);
+    }
+    Ok(resolved)
+}
+
+/// Checked, non-empty segments of `rel`.
+fn normalize(rel: &str) -> Result<Vec<&str>, PathError> {
+    if rel.chars().any(|c| c == '\0' || c.is_control()) {
+        return Err(PathError::Encoding);
+    }
+    if rel.contains('\\') {
+        return Err(PathError::Traversal);
+    }
+    let mut segments = Vec::new();
+    for segment in rel.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return Err(PathError::Traversal),
+            // Windows drive / stream syntax (`C:`, `file::$DATA`)
+            s if s.contains(':') => return Err(PathError::Traversal),
+            s if s.starts_with('.') => return Err(PathError::Hidden),
+            s if is_backup_name(s) => return Err(PathError::Backup),
+            s => segments.push(s),
+        }
+    }
+    Ok(segments)
+}
+
+fn is_backup_name(name: &str) -> bool {
+    if name.ends_with('~') || (name.starts_with('#') && name.ends_with('#')) {
+        return true;
+    }
+    name.rsplit_once('.')
+        .is_some_and(|(_, ext)| BACKUP_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// `%` followed by two hex digits: left over from double encoding.
+fn has_percent_escape(s: &str) -> bool {
+    s.as_bytes()
+        .windows(3)
+        .any(|w| w[0] == b'%' && w[1].is_ascii_hexdigit() && w[2].is_ascii_hexdigit())
+}
+
+fn io_error(e: io::Error) -> PathError {
+    if e.kind() == io::ErrorKind::NotFound {
+        PathError::NotFound
+    } else {
+        PathError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Fresh directory tree per test:
+    /// <tmp>/root/{index.html, a/b.txt, my file.txt, .env, page.html~, page.bak}, <tmp>/secret.txt
+    struct Fixture {
+        base: PathBuf,
+        root: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let base = std::env::temp_dir().join(format!("smn_sys_path_{name}_{}", std::process::id()));
+            let _ = fs::remove_dir_all(&base);
+            let root = base.join("root");
+            fs::create_dir_all(root.join("a")).unwrap();
+            fs::write(root.join("index.html"), "index").unwrap();
+            fs::write(root.join("a/b.txt"), "b").unwrap();
+            fs::write(root.join("my file.txt"), "spaced").unwrap();
+            fs::write(root.join(".env"), "SECRET=1").unwrap();
+            fs::write(root.join("page.html~"), "old").unwrap();
+            fs::write(root.join("page.bak"), "old").unwrap();
+            fs::write(base.join("secret.txt"), "secret").unwrap();
+            Self { base, root }
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.base);
+        }
+    }
+
+    #[test]
+    fn resolves_regular_files() {
+        let fx = Fixture::new("regular");
+        let root = fx.root.canonicalize().unwrap();
+        assert_eq!(resolve_url_path(&fx.root, "index.html").unwrap(), root.join("index.html"));
+        assert_eq!(resolve_url_path(&fx.root, "/a/./b.txt").unwrap(), root.join("a/b.txt"));
+        assert_eq!(resolve_url_path(&fx.root, "a//b.txt").unwrap(), root.join("a/b.txt"));
+        assert_eq!(resolve_url_path(&fx.root, "my%20file.txt").unwrap(), root.join("my file.txt"));
+        assert_eq!(resolve_url_path(&fx.root, "").unwrap(), root);
+    }
+
+    #[test]
+    fn rejects_traversal_payloads() {
+        let fx = Fixture::new("traversal");
+        let payloads = [
+            "../secret.txt",
+            "a/../../secret.txt",
+            "a/../index.html",
+            "..%2fsecret.txt",
+            "..%2Fsecret.txt",
+            "%2e%2e/secret.txt",
+            "%2E%2E%2Fsecret.txt",
+            "a/%2e%2e/%2e%2e/secret.txt",
+            "..\\secret.txt",
+            "..%5csecret.txt",
+            "a%5c..%5c..%5csecret.txt",
+            "C:/Windows/win.ini",
+            "index.html::$DATA",
+        ];
+        for payload in payloads {
+            let result = resolve_url_path(&fx.root, payload);
+            assert!(matches!(result, Err(PathError::Traversal)), "{payload}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_encoding() {
+        let fx = Fixture::new("encoding");
+        let payloads = [
+            "%252e%252e/secret.txt", // double encoded ../
+            "%252e%252e%252fsecret.txt",
+            "%c0%ae%c0%ae/secret.txt", // overlong UTF-8 for ".."
+            "%ff/index.html",
+            "index.html%00.txt",
+            "index.html%0a",
+        ];
+        for payload in payloads {
+            let result = resolve_url_path(&fx.root, payload);
+            assert!(matches!(result, Err(PathError::Encoding)), "{payload}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_hidden_and_backup_files() {
+        let fx = Fixture::new("hidden");
+        for payload in [".env", "%2eenv", "a/.git/config", "....//....//secret.txt"] {
+            let result = resolve_url_path(&fx.root, payload);
+            assert!(matches!(result, Err(PathError::Hidden)), "{payload}: {result:?}");
+        }
+        for payload in ["page.html~", "page.bak", "page.BAK", "#page.html#", "a/b.txt.swp", "index.html.orig"] {
+            let result = resolve_url_path(&fx.root, payload);
+            assert!(matches!(result, Err(PathError::Backup)), "{payload}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn missing_files_are_not_found() {
+        let fx = Fixture::new("missing");
+        for payload in ["nope.html", "a/nope", "/etc/passwd"] {
+            let result = resolve_url_path(&fx.root, payload);
+            assert!(matches!(result, Err(PathError::NotFound)), "{payload}: {result:?}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_out_of_root() {
+        let fx = Fixture::new("symlink");
+        std::os::unix::fs::symlink(fx.base.join("secret.txt"), fx.root.join("leak.txt")).unwrap();
+        std::os::unix::fs::symlink(&fx.base, fx.root.join("up")).unwrap();
+        std::os::unix::fs::symlink(fx.root.join("a/b.txt"), fx.root.join("inside.txt")).unwrap();
+
+        assert!(matches!(resolve_url_path(&fx.root, "leak.txt"), Err(PathError::Outside)));
+        assert!(matches!(resolve_url_path(&fx.root, "up/secret.txt"), Err(PathError::Outside)));
+        let inside = resolve_url_path(&fx.root, "inside.txt").unwrap();
+        assert_eq!(inside, fx.root.canonicalize().unwrap().join("a/b.txt"));
+    }
+
+    #[test]
+    fn resolve_path_does_not_decode() {
+        let fx = Fixture::new("raw");
+        assert!(matches!(resolve_path(&fx.root, "my%20file.txt"), Err(PathError::NotFound)));
+        assert!(resolve_path(&fx.root, "my file.txt").is_ok());
+        assert!(matches!(resolve_path(&fx.root, "../secret.txt"), Err(PathError::Traversal)));
+    }
+}
@@ -0,0 +1,312 @@
+use hyper::{
+    Body, HeaderMap, Method, Response, StatusCode,
+    body::Bytes,
+    header::{
+        ACCEPT_RANGES, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, HeaderValue, IF_RANGE,
+        LAST_MODIFIED, RANGE, X_CONTENT_TYPE_OPTIONS,
+    },
+};
+use std::{io::SeekFrom, path::Path, time::SystemTime};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+
+use crate::systems::sys_mime;
+
+// Bytes read from disk per body chunk.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Serve the file at `path` without buffering it: the body is streamed from disk in chunks.
+/// Handles `HEAD` (headers only), `Range: bytes=…` (one range, 206 / 416) and `If-Range`
+/// against the `ETag` / `Last-Modified` sent with every response.
+/// The `Content-Type` comes from the shared MIME registry (`sys_mime`), sent with `nosniff`;
+/// files stored compressed (`.svgz`) also get their `Content-Encoding`.
+/// Returns None when `path` is not a readable file.
+pub async fn serve_file(method: &Method, headers: &HeaderMap, path: &Path) -> Option<Response<Body>> {
+    let md = tokio::fs::metadata(path).await.ok().filter(|m| m.is_file())?;
+    let mut file = File::open(path).await.ok()?;
+    let len = md.len();
+    let modified = md.modified().ok();
+    let etag = entity_tag(len, modified);
+
+    let range = headers
+        .get(RANGE)
+        .and_then(|v| v.to_str().ok())
+        .filter(|_| if_range_matches(headers, &etag, modified));
+    let (status, start, end) = match range.map(|r| parse_range(r, len)) {
+        Some(RangeRequest::Satisfiable(start, end)) => (StatusCode::PARTIAL_CONTENT, start, end),
+        Some(RangeRequest::Unsatisfiable) => {
+            let mut resp = Response::new(Body::empty());
+            *resp.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+            insert(resp.headers_mut(), CONTENT_RANGE, format!("bytes */{len}"));
+            set_nosniff(resp.headers_mut());
+            return Some(resp);
+        }
+        // No (usable) range: the whole file
+        Some(RangeRequest::Ignored) | None => (StatusCode::OK, 0, len.saturating_sub(1)),
+    };
+    let body_len = if len == 0 { 0 } else { end - start + 1 };
+
+    let body = if *method == Method::HEAD || body_len == 0 {
+        Body::empty()
+    } else {
+        if start > 0 && file.seek(SeekFrom::Start(start)).await.is_err() {
+            return None;
+        }
+        stream_body(file, body_len)
+    };
+
+    let mut resp = Response::new(body);
+    *resp.status_mut() = status;
+    let h = resp.headers_mut();
+    insert(h, CONTENT_TYPE, sys_mime::content_type(path));
+    if let Some(encoding) = sys_mime::content_encoding(path) {
+        h.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding));
+    }
+    set_nosniff(h);
+    h.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    insert(h, CONTENT_LENGTH, body_len.to_string());
+    insert(h, ETAG, etag);
+    if let Some(modified) = modified {
+        insert(h, LAST_MODIFIED, httpdate::fmt_http_date(modified));
+    }
+    if status == StatusCode::PARTIAL_CONTENT {
+        insert(h, CONTENT_RANGE, format!("bytes {start}-{end}/{len}"));
+    }
+    Some(resp)
+}
+
+/// `X-Content-Type-Options: nosniff`: browsers must use the declared `Content-Type`.
+pub fn set_nosniff(headers: &mut HeaderMap) {
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+}
+
+fn insert(headers: &mut HeaderMap, name: hyper::header::HeaderName, value: String) {
+    if let Ok(v) = HeaderValue::from_str(&value) {
+        headers.insert(name, v);
+    }
+}
+
+/// Strong validator from size and modification time (changes whenever the file is rewritten).
+fn entity_tag(len: u64, modified: Option<SystemTime>) -> String {
+    let mtime = modified
+        .and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    format!("\"{len:x}-{mtime:x}\"")
+}
+
+/// `If-Range`: apply the range only if the client's copy is current (no header: always).
+fn if_range_matches(headers: &HeaderMap, etag: &str, modified: Option<SystemTime>) -> bool {
+    let Some(value) = headers.get(IF_RANGE).and_then(|v| v.to_str().ok()) else {
+        return true;
+    };
+    let value = value.trim();
+    if value.starts_with('"') || value.starts_with("W/") {
+        // Weak tags never match (RFC 9110 §13.1.5)
+        return value == etag;
+    }
+    match (httpdate::parse_http_date(value), modified) {
+        // HTTP dates have whole-second precision
+        (Ok(date), Some(modified)) => httpdate::fmt_http_date(modified) == httpdate::fmt_http_date(date),
+        _ => false,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum RangeRequest {
+    /// Inclusive byte range
+    Satisfiable(u64, u64),
+    Unsatisfiable,
+    /// Not a single byte range we serve (other units, several ranges, malformed): send it all
+    Ignored,
+}
+
+/// `bytes=0-499`, `bytes=500-`, `bytes=-500` against a file of `len` bytes.
+fn parse_range(header: &str, len: u64) -> RangeRequest {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Ignored;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Ignored;
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return RangeRequest::Ignored;
+    };
+    let (first, last) = (first.trim(), last.trim());
+    let parsed = match (first.is_empty(), last.is_empty()) {
+        // Suffix: the last N bytes
+        (true, false) => match last.parse::<u64>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(n) => Some((len.saturating_sub(n), len.saturating_sub(1))),
+            Err(_) => None,
+        },
+        (false, true) => first.parse::<u64>().ok().map(|s| (s, len.saturating_sub(1))),
+        (false, false) => match (first.parse::<u64>(), last.parse::<u64>()) {
+            (Ok(s), Ok(e)) if s <= e => Some((s, e.min(len.saturating_sub(1)))),
+            _ => None,
+        },
+        (true, true) => None,
+    };
+    match parsed {
+        Some((start, end)) if start < len && start <= end => RangeRequest::Satisfiable(start, end),
+        Some(_) => RangeRequest::Unsatisfiable,
+        None => RangeRequest::Ignored,
+    }
+}
+
+/// Body that reads `remaining` bytes from `file` (already positioned) in `CHUNK_SIZE` chunks.
+fn stream_body(mut file: File, mut remaining: u64) -> Body {
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        while remaining > 0 {
+            let want = remaining.min(CHUNK_SIZE as u64) as usize;
+            let read = match file.read(&mut buf[..want]).await {
+                Ok(0) => break, // truncated while serving
+                Ok(n) => n,
+                Err(e) => {
+                    eprintln!("WARN: Failed to read file while streaming: {e}");
+                    sender.abort();
+                    return;
+                }
+            };
+            // Client went away
+            if sender.send_data(Bytes::copy_from_slice(&buf[..read])).await.is_err() {
+                return;
+            }
+            remaining -= read as u64;
+        }
+    });
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use RangeRequest::{Ignored, Satisfiable, Unsatisfiable};
+
+    #[test]
+    fn parses_single_byte_ranges() {
+        assert_eq!(parse_range("bytes=0-499", 1000), Satisfiable(0, 499));
+        assert_eq!(parse_range("bytes=500-", 1000), Satisfiable(500, 999));
+        assert_eq!(parse_range("bytes=-500", 1000), Satisfiable(500, 999));
+        assert_eq!(parse_range(" bytes= 10 - 19 ", 1000), Satisfiable(10, 19));
+        assert_eq!(parse_range("bytes=999-999", 1000), Satisfiable(999, 999));
+    }
+
+    #[test]
+    fn clamps_ranges_past_the_end() {
+        assert_eq!(parse_range("bytes=900-5000", 1000), Satisfiable(900, 999));
+        // Suffix longer than the file: the whole file
+        assert_eq!(parse_range("bytes=-5000", 1000), Satisfiable(0, 999));
+    }
+
+    #[test]
+    fn rejects_unsatisfiable_ranges() {
+        assert_eq!(parse_range("bytes=1000-", 1000), Unsatisfiable);
+        assert_eq!(parse_range("bytes=1000-1500", 1000), Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 1000), Unsatisfiable);
+    }
+
+    #[test]
+    fn ignores_ranges_it_does_not_serve() {
+        for header in [
+            "bytes=0-1,5-9",
+            "items=0-9",
+            "bytes=",
+            "bytes=-",
+            "bytes=abc-9",
+            "bytes=9-0",
+            "bytes=0-x",
+            "0-9",
+        ] {
+            assert_eq!(parse_range(header, 1000), Ignored, "{header}");
+        }
+    }
+
+    #[test]
+    fn zero_length_files_have_no_satisfiable_range() {
+        assert_eq!(parse_range("bytes=0-499", 0), Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-", 0), Unsatisfiable);
+        assert_eq!(parse_range("bytes=-500", 0), Unsatisfiable);
+    }
+
+    fn if_range(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_RANGE, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn if_range_validators() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let etag = entity_tag(1000, Some(modified));
+
+        assert!(if_range_matches(&HeaderMap::new(), &etag, Some(modified)));
+        // Strong tags must match exactly, weak tags never do
+        assert!(if_range_matches(&if_range(&etag), &etag, Some(modified)));
+        assert!(!if_range_matches(&if_range("\"other\""), &etag, Some(modified)));
+        assert!(!if_range_matches(&if_range(&format!("W/{etag}")), &etag, Some(modified)));
+        // Dates compare at whole-second precision
+        let date = httpdate::fmt_http_date(modified);
+        assert!(if_range_matches(&if_range(&date), &etag, Some(modified)));
+        let older = httpdate::fmt_http_date(modified - Duration::from_secs(5));
+        assert!(!if_range_matches(&if_range(&older), &etag, Some(modified)));
+        assert!(!if_range_matches(&if_range(&date), &etag, None));
+        assert!(!if_range_matches(&if_range("not a date"), &etag, Some(modified)));
+    }
+
+    async fn serve(path: &Path, method: Method, range: Option<&str>) -> (StatusCode, HeaderMap, Vec<u8>) {
+        let mut headers = HeaderMap::new();
+        if let Some(range) = range {
+            headers.insert(RANGE, HeaderValue::from_str(range).unwrap());
+        }
+        let resp = serve_file(&method, &headers, path).await.expect("file is served");
+        let (parts, body) = resp.into_parts();
+        let bytes = hyper::body::to_bytes(body).await.unwrap();
+        (parts.status, parts.headers, bytes.to_vec())
+    }
+
+    #[tokio::test]
+    async fn serves_ranges_head_and_empty_files() {
+        let dir = std::env::temp_dir().join(format!("smn_sys_static_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("data.txt");
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&file, &content).unwrap();
+        let empty = dir.join("empty.txt");
+        std::fs::write(&empty, "").unwrap();
+
+        let (status, headers, body) = serve(&file, Method::GET, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, content);
+        assert_eq!(headers[ACCEPT_RANGES], "bytes");
+
+        let (status, headers, body) = serve(&file, Method::GET, Some("bytes=70000-70009")).await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(headers[CONTENT_RANGE], "bytes 70000-70009/200000");
+        assert_eq!(body, &content[70_000..70_010]);
+
+        let (status, headers, body) = serve(&file, Method::HEAD, Some("bytes=-100")).await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(headers[CONTENT_LENGTH], "100");
+        assert!(body.is_empty());
+
+        let (status, headers, _) = serve(&file, Method::GET, Some("bytes=300000-")).await;
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(headers[CONTENT_RANGE], "bytes */200000");
+
+        let (status, headers, body) = serve(&empty, Method::GET, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[CONTENT_LENGTH], "0");
+        assert!(body.is_empty());
+        let (status, _, _) = serve(&empty, Method::GET, Some("bytes=0-")).await;
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+
+        assert!(serve_file(&Method::GET, &HeaderMap::new(), &dir).await.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,34 +1,60 @@
 
 (function () {
+  // Slot comments around mounted components (development mode), as written by the server:
+  // <!--smn-component:begin {"name":"header","args":[],"props":{}}-->…<!--smn-component:end-->
+  const SLOT_BEGIN = 'smn-component:begin ';
+  const SLOT_END = 'smn-component:end';
+
   class ComponentManager {
     /**
      * @param {Object} options
-     * @param {string}  [options.basePath='/components'] - Base route for components.
+     * @param {string}  [options.basePath='/components'] - Base route for components; an absolute URL
+     *                                                    (`https://host/components`) embeds from another site
+     *                                                    (the server must allow this page's origin in cors.json).
+     * @param {RequestCredentials} [options.credentials='same-origin'] - Cookies for component requests;
+     *                                                    'include' sends them cross-origin too.
      * @param {boolean} [options.extractBody=true]       - If true, extracts <body> innerHTML from full documents.
      * @param {boolean} [options.fadeIn=false]           - If true, fade in newly mounted content.
      * @param {number}  [options.fadeInDuration=250]     - Fade duration in ms.
      * @param {string}  [options.fadeInEasing='ease-out']- CSS easing for fade.
+     * @param {boolean|'auto'} [options.hotReload='auto'] - Re-mount components when their template changes.
+     *                                                    'auto': when the server runs in development mode.
      */
     constructor({
       basePath = '/components',
+      credentials = 'same-origin',
       extractBody = true,
       fadeIn = false,
       fadeInDuration = 250,
       fadeInEasing = 'ease-out',
+      hotReload = 'auto',
     } = {}) {
       if (typeof window === 'undefined' || typeof window.fetch !== 'function') {
         throw new Error('ComponentManager: must run in a browser environment with fetch support.');
       }
       this.basePath = (basePath || '/components').replace(/\/+$/, '');
+      this.credentials = credentials;
+      // Server the components come from; asset links and /events are resolved against it
+      this.serverOrigin = new URL(this.basePath, window.location.href).origin;
       this.extractBody = !!extractBody;
 
       // Fade options
       this.fadeIn = !!fadeIn;
       this.fadeInDuration = Math.max(0, Number(fadeInDuration) || 0);
       this.fadeInEasing = String(fadeInEasing || 'ease-out');
+
+      // Hot reload: off until enabled (explicitly, or by a development-mode server)
+      this.hotReload = hotReload;
+      this.hotReloadActive = false;
+      this._slotNames = new Set();
+      this._broadcast = null;
+      if (hotReload === true) this.startHotReload();
     }
 
-    /** Fetch a component’s HTML (as text) */
+    /**
+     * Fetch a component’s HTML (as text).
+     * `compArgs` is either the positional array or a plain object of named props (sent as `compProps`).
+     */
     async fetchComponent(name, compArgs = [], init) {
       if (!name) throw new Error('ComponentManager.fetchComponent: name is required.');
       const url = `${this.basePath}/${encodeURIComponent(name)}`;
@@ -36,21 +62,98 @@
       const res = await fetch(url, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json', ...(init && init.headers) },
-        body: JSON.stringify({ compArgs }),
+        body: JSON.stringify(ComponentManager.toPayload(compArgs)),
+        credentials: this.credentials,
         ...(init || {}),
       });
 
+      this._noteHotReload(res);
       const text = await res.text().catch(() => '');
       if (!res.ok) {
-        throw new Error(`Component "${name}" failed: ${res.status} ${res.statusText}\n${text}`);
+        const type = res.headers.get('Content-Type') || '';
+        // Development mode: the server sends a visible error box, mount it in place of the component
+        if (res.headers.get('X-Component-Error') && type.startsWith('text/html')) {
+          console.error(`Component "${name}" failed: ${res.status} ${res.headers.get('X-Component-Error')}`);
+          return text;
+        }
+        throw new Error(`Component "${name}" failed: ${res.status} ${res.statusText}\n${ComponentManager.describeProblem(text)}`);
       }
+      this.ensureAssets(ComponentManager.parseLinkHeader(res.headers.get('Link')));
       return text;
     }
 
+    /**
+     * Add a component's stylesheets / scripts to <head>, once per URL.
+     * @param {{stylesheets?: string[], scripts?: string[]}} assets
+     */
+    ensureAssets({ stylesheets = [], scripts = [] } = {}) {
+      stylesheets = stylesheets.map(url => this._serverUrl(url));
+      scripts = scripts.map(url => this._serverUrl(url));
+      const present = (attr, url) =>
+        Array.from(document.querySelectorAll(`[${attr}]`)).some(el => el.getAttribute(attr) === url);
+      const withoutQuery = url => url.split('?')[0];
+      for (const href of stylesheets) {
+        if (present('href', href)) continue;
+        const link = document.createElement('link');
+        link.rel = 'stylesheet';
+        link.href = href;
+        // A newer version of a component style (`?v=` changed) replaces the old one
+        const stale = Array.from(document.querySelectorAll('link[rel="stylesheet"][href]'))
+          .find(el => withoutQuery(el.getAttribute('href')) === withoutQuery(href));
+        if (stale) stale.replaceWith(link);
+        else document.head.appendChild(link);
+      }
+      for (const src of scripts) {
+        if (present('src', src)) continue;
+        const script = document.createElement('script');
+        script.src = src;
+        script.defer = true;
+        document.head.appendChild(script);
+      }
+    }
+
+    /**
+     * Fetch many components in one round trip via `${basePath}/_batch`.
+     * Resolves to `[{ name, status, html }]` in request order; failed items carry their status.
+     */
+    async fetchBatch(items, init) {
+      const url = `${this.basePath}/_batch`;
+      const body = items.map(({ name, args = [] }) => ({ name, ...ComponentManager.toPayload(args) }));
+
+      const res = await fetch(url, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json', ...(init && init.headers) },
+        body: JSON.stringify(body),
+        credentials: this.credentials,
+        ...(init || {}),
+      });
+
+      this._noteHotReload(res);
+      if (!res.ok) {
+        const text = await res.text().catch(() => '');
+        throw new Error(`Component batch failed: ${res.status} ${res.statusText}\n${text}`);
+      }
+      return res.json();
+    }
+
     /** Mount a component into the DOM */
     async mount(target, name, compArgs = [], { mode = 'replace' } = {}) {
       const el = this._resolveTarget(target);
-      const html = this._toEmbeddable(await this.fetchComponent(name, compArgs));
+      const html = await this.fetchComponent(name, compArgs);
+      return this.mountHtml(el, html, { mode, slot: ComponentManager.slotOf(name, compArgs) });
+    }
+
+    /**
+     * Mount already-fetched component HTML into the DOM.
+     * With hot reload active, `slot` ({ name, args, props }) marks the content for re-mounting.
+     */
+    mountHtml(target, html, { mode = 'replace', slot = null } = {}) {
+      const el = this._resolveTarget(target);
+      html = this._toEmbeddable(html);
+      if (slot && this.hotReloadActive) {
+        html = ComponentManager.slotComments(slot, html);
+        this._watchSlotName(slot.name);
+      }
 
       switch (mode) {
         case 'replace': {
@@ -106,11 +209,15 @@
         }
 
         default:
-          throw new Error(`ComponentManager.mount: unknown mode "${mode}"`);
+          throw new Error(`ComponentManager.mountHtml: unknown mode "${mode}"`);
       }
     }
 
-    /** Batch: mount multiple components (in parallel). */
+    /**
+     * Batch: mount multiple components with a single `_batch` request.
+     * Every successful item is mounted; afterwards the first failure (if any) is thrown.
+     * Falls back to one request per component if the batch endpoint is unavailable.
+     */
     async mountGroup(
       batch,
       {
@@ -120,9 +227,47 @@
     ) {
       if (!Array.isArray(batch) || batch.length === 0) return [];
       const total = batch.length;
+      const items = batch.map(item => item || {});
+
+      let results;
+      try {
+        results = await this.fetchBatch(items);
+      } catch {
+        return this._mountEach(items, { mode, onProgress });
+      }
 
-      const jobs = batch.map(async (item, i) => {
-        const { target, name, args = [], mountMode = mode } = item || {};
+      let firstError = null;
+      const mounted = items.map((item, i) => {
+        const { target, name, args = [], mountMode = mode } = item;
+        const result = results[i] || { status: 0, html: 'missing from batch response' };
+        try {
+          if (result.status < 200 || result.status >= 300) {
+            // Development mode: mount the error box so the broken slot is visible
+            if (result.html) this.mountHtml(target, result.html, { mode: mountMode });
+            const detail = result.error ? ComponentManager.describeProblem(JSON.stringify(result.error)) : '';
+            throw new Error(`Component "${name}" failed: ${result.status}\n${detail}`);
+          }
+          this.ensureAssets(result);
+          const slot = ComponentManager.slotOf(name, args);
+          const res = this.mountHtml(target, result.html, { mode: mountMode, slot });
+          onProgress && onProgress(i + 1, total, { ok: true, target, name, res });
+          return res;
+        } catch (err) {
+          onProgress && onProgress(i + 1, total, { ok: false, target, name, error: err });
+          firstError = firstError || err;
+          return null;
+        }
+      });
+
+      if (firstError) throw firstError;
+      return mounted;
+    }
+
+    /** Mount a group one request per component (in parallel). */
+    async _mountEach(items, { mode, onProgress }) {
+      const total = items.length;
+      const jobs = items.map(async (item, i) => {
+        const { target, name, args = [], mountMode = mode } = item;
         try {
           const res = await this.mount(target, name, args, { mode: mountMode });
           onProgress && onProgress(i + 1, total, { ok: true, target, name, res });
@@ -141,8 +286,142 @@
       return this._toEmbeddable(await this.fetchComponent(name, compArgs, init));
     }
 
+    // ---------- hot reload ----------
+
+    /**
+     * Listen for template changes on `/events` and re-mount the affected components.
+     * Uses `BroadcastManager` (broadcastManager.js) when loaded, a plain EventSource otherwise.
+     */
+    startHotReload() {
+      if (this.hotReloadActive || this.hotReload === false) return;
+      this.hotReloadActive = true;
+      const onUpdate = name => { void this.reloadComponent(name); };
+      if (typeof window.BroadcastManager === 'function') {
+        this._broadcast = new window.BroadcastManager([], onUpdate);
+      } else if (typeof window.EventSource === 'function') {
+        const es = new EventSource(this._serverUrl('/events'), { withCredentials: this.credentials === 'include' });
+        es.addEventListener('update', e => {
+          const name = e.data.trim();
+          if (this._slotNames.has(name)) onUpdate(name);
+        });
+        this._broadcast = { addContextID: () => {}, close: () => es.close() };
+      }
+      for (const slot of this.findSlots()) this._watchSlotName(slot.info.name);
+    }
+
+    /**
+     * Fetch `name` again and swap it into every slot showing it, keeping each slot's arguments.
+     * Resolves to the number of slots updated.
+     */
+    async reloadComponent(name) {
+      const slots = this.findSlots().filter(slot => slot.info.name === name);
+      let updated = 0;
+      for (const { begin, end, info } of slots) {
+        // Slots inside an already re-mounted slot were replaced along with it
+        if (!begin.isConnected || !end.isConnected) continue;
+        try {
+          const body = JSON.stringify({ compArgs: info.args || [], compProps: info.props || {} });
+          const html = this._toEmbeddable(await this.fetchComponent(name, [], { body }));
+          while (begin.nextSibling && begin.nextSibling !== end) begin.nextSibling.remove();
+          const fragment = this._htmlToFragment(html);
+          const inserted = this._nodesOfFragment(fragment);
+          end.parentNode.insertBefore(fragment, end);
+          if (this.fadeIn) this._runFade(inserted);
+          updated++;
+        } catch (err) {
+          console.warn(`ComponentManager: hot reload of "${name}" failed`, err);
+        }
+      }
+      // Re-mounted content may contain components not seen before
+      for (const slot of this.findSlots()) this._watchSlotName(slot.info.name);
+      if (updated) console.log(`[ComponentManager] Reloaded "${name}" (${updated} slot(s))`);
+      return updated;
+    }
+
+    /** Slot comments in `root`: `[{ begin, end, info: { name, args, props } }]`, outer slots first. */
+    findSlots(root = document) {
+      const walker = document.createTreeWalker(root, NodeFilter.SHOW_COMMENT);
+      const open = [];
+      const slots = [];
+      for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+        const text = node.data;
+        if (text.startsWith(SLOT_BEGIN)) {
+          let info = null;
+          try { info = JSON.parse(text.slice(SLOT_BEGIN.length)); } catch { /* malformed: skip */ }
+          const slot = { begin: node, end: null, info };
+          open.push(slot);
+          slots.push(slot);
+        } else if (text === SLOT_END && open.length) {
+          open.pop().end = node;
+        }
+      }
+      return slots.filter(slot => slot.end && slot.info && slot.info.name);
+    }
+
+    /** Wrap component HTML in slot comments (`--` is escaped so the comment cannot end early). */
+    static slotComments({ name, args = [], props = {} }, html) {
+      const info = JSON.stringify({ name, args, props }).replace(/--/g, '-\\u002d');
+      return `<!--${SLOT_BEGIN}${info}-->${html}<!--${SLOT_END}-->`;
+    }
+
+    /** Slot info for a mount call's `compArgs` (positional array or named props). */
+    static slotOf(name, compArgs) {
+      const { compArgs: args = [], compProps: props = {} } = ComponentManager.toPayload(compArgs);
+      return { name, args, props };
+    }
+
+    _noteHotReload(res) {
+      if (this.hotReload === 'auto' && res.headers.get('X-Component-Hot-Reload')) this.startHotReload();
+    }
+
+    _watchSlotName(name) {
+      if (this._slotNames.has(name)) return;
+      this._slotNames.add(name);
+      if (this._broadcast) this._broadcast.addContextID(name);
+    }
+
+    /** Build the request body: arrays → { compArgs }, plain objects → { compProps } */
+    static toPayload(compArgs) {
+      if (compArgs && typeof compArgs === 'object' && !Array.isArray(compArgs)) {
+        return { compArgs: [], compProps: compArgs };
+      }
+      return { compArgs: Array.isArray(compArgs) ? compArgs : [] };
+    }
+
+    /** Problem details JSON (`{ title, detail?, errors }`) → readable text; other bodies as is. */
+    static describeProblem(body) {
+      try {
+        const p = JSON.parse(body);
+        if (!p || typeof p !== 'object' || !p.title) return body;
+        const head = p.detail ? `${p.title}: ${p.detail}` : p.title;
+        return [head, ...(p.errors || []).map(e => `- ${e}`)].join('\n');
+      } catch {
+        return body;
+      }
+    }
+
+    /** `Link: </a.css>; rel="stylesheet", </b.js>; rel="preload"; as="script"` → asset lists */
+    static parseLinkHeader(header) {
+      const assets = { stylesheets: [], scripts: [] };
+      if (!header) return assets;
+      for (const part of header.split(/,(?=\s*<)/)) {
+        const match = part.match(/<([^>]*)>(.*)/);
+        if (!match) continue;
+        const [, url, params] = match;
+        if (/rel="?stylesheet"?/i.test(params)) assets.stylesheets.push(url);
+        else if (/as="?script"?/i.test(params)) assets.scripts.push(url);
+      }
+      return assets;
+    }
+
     // ---------- internals ----------
 
+    /** Root-relative server URL (`/components/x.css`), made absolute when embedding from another site */
+    _serverUrl(url) {
+      if (this.serverOrigin === window.location.origin || !url.startsWith('/') || url.startsWith('//')) return url;
+      return this.serverOrigin + url;
+    }
+
     _resolveTarget(target) {
       if (target instanceof Element) return target;
       if (typeof target === 'string') {
@@ -218,8 +497,9 @@
 
   // ---------- Global helpers (singleton manager) ----------
 
-  async function applyComponent(targetElement, targetComponent, componentArgs) {
-    const cm = (window.cm instanceof ComponentManager)
+  /** The page's `window.cm`, created with site-wide defaults if the page did not set one up. */
+  function defaultManager() {
+    return (window.cm instanceof ComponentManager)
       ? window.cm
       : (window.cm = new ComponentManager({
           basePath: '/components',
@@ -229,8 +509,13 @@
           fadeInDuration: 250,
           fadeInEasing: 'ease-out',
         }));
+  }
+
+  async function applyComponent(targetElement, targetComponent, componentArgs) {
+    const cm = defaultManager();
 
-    const args = Array.isArray(componentArgs)
+    // Arrays and plain objects (named props) pass through; scalars become a one-item list.
+    const args = (Array.isArray(componentArgs) || (componentArgs && typeof componentArgs === 'object'))
       ? componentArgs
       : (componentArgs == null ? [] : [String(componentArgs)]);
 
@@ -260,15 +545,7 @@
    * @param {{mode?:string,onProgress?:(i:number,total:number,res:any)=>void}} opts
    */
   async function applyComponentsGroup(batch, opts = {}) {
-    const cm = (window.cm instanceof ComponentManager)
-      ? window.cm
-      : (window.cm = new ComponentManager({
-          basePath: '/components',
-          extractBody: true,
-          fadeIn: true,
-          fadeInDuration: 250,
-          fadeInEasing: 'ease-out',
-        }));
+    const cm = defaultManager();
 
     if (document.readyState === 'loading' && batch.some(it => typeof it?.target === 'string')) {
       await new Promise(res => document.addEventListener('DOMContentLoaded', res, { once: true }));
@@ -276,7 +553,42 @@
     return cm.mountGroup(batch, opts);
   }
 
+  /**
+   * Mount any <smn-component name="..." args='[...]' props='{...}'> markers the server
+   * did not expand (e.g. when the page is served without server-side includes).
+   */
+  async function mountIncludeMarkers(root = document) {
+    const markers = Array.from(root.querySelectorAll('smn-component[name]'));
+    const parse = (raw, fallback) => {
+      if (!raw) return fallback;
+      try { return JSON.parse(raw); } catch { return fallback; }
+    };
+    if (markers.length === 0) return [];
+    const batch = markers.map(el => {
+      const props = parse(el.getAttribute('props'), null);
+      const args = props && typeof props === 'object' ? props : parse(el.getAttribute('args'), []);
+      return { target: el, name: el.getAttribute('name'), args };
+    });
+    // One round trip for all markers; failed ones stay in place.
+    return applyComponentsGroup(batch, { mode: 'outerReplace' })
+      .catch(err => { console.warn('mountIncludeMarkers:', err); return []; });
+  }
+
+  /** Start hot reload when the server marked expanded components with slot comments (development mode). */
+  function watchServerSlots() {
+    const cm = defaultManager();
+    if (cm.hotReload === 'auto' && cm.findSlots().length > 0) cm.startHotReload();
+  }
+
+  const onReady = () => { watchServerSlots(); void mountIncludeMarkers(); };
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', onReady, { once: true });
+  } else {
+    onReady();
+  }
+
   window.ComponentManager = ComponentManager;
   window.applyComponent = applyComponent;
   window.applyComponentsGroup = applyComponentsGroup;
+  window.mountIncludeMarkers = mountIncludeMarkers;
 })();
\ No newline at end of file